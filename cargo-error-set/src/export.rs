@@ -0,0 +1,224 @@
+use error_set_core::graph::{FromSource, plan_froms};
+use error_set_core::model::Common;
+use error_set_core::suggest::{tokens_to_string, type_path_to_string};
+use error_set_core::validate::variant_shape;
+
+use crate::scan::ResolvedErrorSet;
+
+/// The graph of one [ResolvedErrorSet], independent of the output format
+struct Graph {
+    label: String,
+    /// `(id, name, variants)`
+    sets: Vec<(String, String, Vec<String>)>,
+    /// `(id, source type)`
+    source_types: Vec<(String, String)>,
+    /// Generated `From<from> for to`. `(from id, to id, cfg)`
+    set_edges: Vec<(String, String, Option<String>)>,
+    /// Generated `From<source type> for to`. `(source type id, to id, label)`
+    source_type_edges: Vec<(String, String, String)>,
+}
+
+/// Nodes are sets with their variants and source types. Edges are the generated `From` conversions.
+fn to_graphs(error_sets: &[ResolvedErrorSet]) -> Vec<Graph> {
+    let mut graphs = Vec::new();
+    for (unit_index, error_set) in error_sets.iter().enumerate() {
+        let set_id = |name: &syn::Ident| format!("u{unit_index}_{name}");
+        let mut graph = Graph {
+            label: error_set.label.clone(),
+            sets: Vec::new(),
+            source_types: Vec::new(),
+            set_edges: Vec::new(),
+            source_type_edges: Vec::new(),
+        };
+        for node in &error_set.graph {
+            let error_enum = &node.error_enum;
+            let to = set_id(&error_enum.error_name);
+            graph.sets.push((
+                to.clone(),
+                error_enum.error_name.to_string(),
+                error_enum
+                    .error_variants
+                    .iter()
+                    .map(variant_shape)
+                    .collect(),
+            ));
+            for from_plan in plan_froms(node, &error_set.graph) {
+                if from_plan.suppressed.is_some() {
+                    continue;
+                }
+                let cfg = (!from_plan.cfg_attributes.is_empty()).then(|| {
+                    from_plan
+                        .cfg_attributes
+                        .iter()
+                        .map(tokens_to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+                let (source_type, label) = match &from_plan.from {
+                    FromSource::Set {
+                        error_enum: from_error_enum,
+                        ..
+                    } => {
+                        graph.set_edges.push((
+                            set_id(&from_error_enum.error_name),
+                            to.clone(),
+                            cfg,
+                        ));
+                        continue;
+                    }
+                    FromSource::SourceType {
+                        variant,
+                        source_type,
+                    } => (type_path_to_string(source_type), variant.name().to_string()),
+                    FromSource::BoxedSourceType {
+                        variant,
+                        inner_type,
+                    } => (
+                        type_path_to_string(inner_type),
+                        format!("boxed into {}", variant.name()),
+                    ),
+                };
+                let label = match cfg {
+                    Some(cfg) => format!("{label} {cfg}"),
+                    None => label,
+                };
+                let source_type_id =
+                    match graph.source_types.iter().find(|(_, e)| *e == source_type) {
+                        Some((id, _)) => id.clone(),
+                        None => {
+                            let id = format!("u{unit_index}_t{}", graph.source_types.len());
+                            graph.source_types.push((id.clone(), source_type));
+                            id
+                        }
+                    };
+                graph
+                    .source_type_edges
+                    .push((source_type_id, to.clone(), label));
+            }
+        }
+        graphs.push(graph);
+    }
+    graphs
+}
+
+/// The graph in Graphviz DOT. Source type edges are dashed.
+pub(crate) fn to_dot(error_sets: &[ResolvedErrorSet]) -> String {
+    fn escape(input: &str) -> String {
+        input.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+    let mut dot = String::from("digraph error_sets {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (index, graph) in to_graphs(error_sets).into_iter().enumerate() {
+        dot.push_str(&format!(
+            "    subgraph cluster_{index} {{\n        label=\"{}\";\n",
+            escape(&graph.label)
+        ));
+        for (id, name, variants) in &graph.sets {
+            let mut label = format!("{}\\n\\n", escape(name));
+            for variant in variants {
+                label.push_str(&format!("{}\\l", escape(variant)));
+            }
+            dot.push_str(&format!("        {id} [label=\"{label}\"];\n"));
+        }
+        for (id, source_type) in &graph.source_types {
+            dot.push_str(&format!(
+                "        {id} [label=\"{}\", shape=ellipse];\n",
+                escape(source_type)
+            ));
+        }
+        dot.push_str("    }\n");
+        for (from, to, cfg) in &graph.set_edges {
+            match cfg {
+                Some(cfg) => dot.push_str(&format!(
+                    "    {from} -> {to} [label=\"{}\"];\n",
+                    escape(cfg)
+                )),
+                None => dot.push_str(&format!("    {from} -> {to};\n")),
+            }
+        }
+        for (from, to, label) in &graph.source_type_edges {
+            dot.push_str(&format!(
+                "    {from} -> {to} [label=\"{}\", style=dashed];\n",
+                escape(label)
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// The graph as a Mermaid flowchart. Source type edges are dotted.
+pub(crate) fn to_mermaid(error_sets: &[ResolvedErrorSet]) -> String {
+    fn escape(input: &str) -> String {
+        input
+            .replace('"', "#quot;")
+            .replace('<', "#lt;")
+            .replace('>', "#gt;")
+            .replace('|', "#124;")
+    }
+    let mut mermaid = String::from("flowchart LR\n");
+    for (index, graph) in to_graphs(error_sets).into_iter().enumerate() {
+        mermaid.push_str(&format!(
+            "    subgraph u{index} [\"{}\"]\n",
+            escape(&graph.label)
+        ));
+        for (id, name, variants) in &graph.sets {
+            let mut label = escape(name);
+            for variant in variants {
+                label.push_str(&format!("<br/>{}", escape(variant)));
+            }
+            mermaid.push_str(&format!("        {id}[\"{label}\"]\n"));
+        }
+        for (id, source_type) in &graph.source_types {
+            mermaid.push_str(&format!("        {id}([\"{}\"])\n", escape(source_type)));
+        }
+        mermaid.push_str("    end\n");
+        for (from, to, cfg) in &graph.set_edges {
+            match cfg {
+                Some(cfg) => {
+                    mermaid.push_str(&format!("    {from} -->|\"{}\"| {to}\n", escape(cfg)))
+                }
+                None => mermaid.push_str(&format!("    {from} --> {to}\n")),
+            }
+        }
+        for (from, to, label) in &graph.source_type_edges {
+            mermaid.push_str(&format!("    {from} -.->|\"{}\"| {to}\n", escape(label)));
+        }
+    }
+    mermaid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error_set_core::ast::AstErrorSet;
+    use error_set_core::resolve_error_set;
+
+    fn error_sets(tokens: proc_macro2::TokenStream) -> Vec<ResolvedErrorSet> {
+        let error_set = resolve_error_set(syn::parse2::<AstErrorSet>(tokens).unwrap()).unwrap();
+        vec![ResolvedErrorSet {
+            label: "error_set! at src/lib.rs:1".to_string(),
+            graph: error_set.graph,
+            error_structs: error_set.error_structs,
+        }]
+    }
+
+    #[test]
+    fn test_export() {
+        let error_sets = error_sets(quote::quote! {
+            MediaError := DownloadError || { Parse { line: u32 } }
+            DownloadError := {
+                IoError(std::io::Error),
+                Timeout,
+            }
+        });
+        let dot = to_dot(&error_sets);
+        assert!(dot.contains("u0_DownloadError -> u0_MediaError;"));
+        assert!(dot.contains("u0_t0 [label=\"std::io::Error\", shape=ellipse];"));
+        assert!(dot.contains("u0_t0 -> u0_DownloadError [label=\"IoError\", style=dashed];"));
+        assert!(dot.contains("Parse { line: u32 }\\l"));
+        let mermaid = to_mermaid(&error_sets);
+        assert!(mermaid.contains("u0_DownloadError --> u0_MediaError"));
+        assert!(mermaid.contains("u0_t0 -.->|\"IoError\"| u0_DownloadError"));
+        assert!(mermaid.contains("u0_MediaError[\"MediaError<br/>"));
+    }
+}
@@ -0,0 +1,263 @@
+//! `cargo error-set` - inspects the error sets of a crate without compiling it, using the same parser and resolver
+//! as the `error_set!` macro.
+
+mod export;
+mod migrate;
+mod scan;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use error_set_core::graph::explain;
+use error_set_core::suggest::tokens_to_string;
+use error_set_core::validate::variant_shape;
+use export::{to_dot, to_mermaid};
+use migrate::{Migration, migrate_file, rewrite};
+use scan::{ResolvedErrorSet, crate_path, load, rust_files};
+
+const USAGE: &str = "\
+Inspect the error sets of a crate
+
+Usage: cargo error-set <COMMAND> [OPTIONS] [CRATE_DIR]
+
+Commands:
+  list     Print the resolved sets and their variants
+  graph    Print the `From` conversions generated between sets and source types, and the ones that are not.
+           As a diagram with `--format dot` or `--format mermaid`
+  check    Resolve and validate every `error_set!` and `error_set_part!` without compiling
+  combine  Combine the `error_set_part!`s into `src/error_set.rs`, like `combine_error_set_parts` in `build.rs`
+  migrate  Translate `thiserror` enums into `error_set!` declarations, writing sets that contain other sets with `||`.
+           Prints the translations, or replaces the enums with `--write`
+
+Options:
+  --format <FORMAT>  Output format of `graph` - `text` (default), `dot` or `mermaid`
+  --write            Replace the enums in the files with `migrate`, instead of printing the translations
+
+CRATE_DIR defaults to the current directory";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    // Dev Note: Cargo passes the subcommand name when run as `cargo error-set`
+    if args.peek().is_some_and(|e| e == "error-set") {
+        args.next();
+    }
+    let Some(command) = args.next() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let mut crate_dir = PathBuf::from(".");
+    let mut format = GraphFormat::Text;
+    let mut write = false;
+    while let Some(arg) = args.next() {
+        if arg == "--write" {
+            write = true;
+            continue;
+        }
+        if arg != "--format" {
+            crate_dir = PathBuf::from(arg);
+            continue;
+        }
+        format = match args.next().as_deref() {
+            Some("text") => GraphFormat::Text,
+            Some("dot") => GraphFormat::Dot,
+            Some("mermaid") => GraphFormat::Mermaid,
+            _ => {
+                eprintln!("Expected `text`, `dot` or `mermaid` after `--format`\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        };
+    }
+    let is_success = match command.as_str() {
+        "list" => list(&crate_dir),
+        "graph" => graph(&crate_dir, format),
+        "check" => check(&crate_dir),
+        "combine" => combine(&crate_dir),
+        "migrate" => migrate(&crate_dir, write),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            true
+        }
+        _ => {
+            eprintln!("Unknown command `{command}`\n\n{USAGE}");
+            false
+        }
+    };
+    if is_success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn list(crate_dir: &Path) -> bool {
+    print_error_sets(crate_dir, |error_set| {
+        for node in &error_set.graph {
+            let error_enum = &node.error_enum;
+            println!(
+                "    {}{} := {{",
+                error_enum.error_name,
+                generics_to_string(&error_enum.generics)
+            );
+            for variant in &error_enum.error_variants {
+                println!("        {},", variant_shape(variant));
+            }
+            println!("    }}");
+        }
+        for error_struct in &error_set.error_structs {
+            println!(
+                "    struct {}{}",
+                error_struct.r#struct.ident,
+                tokens_to_string(&error_struct.r#struct.generics)
+            );
+        }
+    })
+}
+
+enum GraphFormat {
+    Text,
+    Dot,
+    Mermaid,
+}
+
+fn graph(crate_dir: &Path, format: GraphFormat) -> bool {
+    let render = match format {
+        GraphFormat::Text => return graph_text(crate_dir),
+        GraphFormat::Dot => to_dot,
+        GraphFormat::Mermaid => to_mermaid,
+    };
+    let (error_sets, diagnostics) = load(crate_dir);
+    print!("{}", render(&error_sets));
+    for diagnostic in &diagnostics {
+        eprintln!("\n{diagnostic}");
+    }
+    diagnostics.is_empty()
+}
+
+fn graph_text(crate_dir: &Path) -> bool {
+    print_error_sets(crate_dir, |error_set| {
+        for (index, node) in error_set.graph.iter().enumerate() {
+            if index != 0 {
+                println!();
+            }
+            for line in explain(node, &error_set.graph).lines() {
+                println!("    {line}");
+            }
+        }
+    })
+}
+
+fn check(crate_dir: &Path) -> bool {
+    let (error_sets, diagnostics) = load(crate_dir);
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}\n");
+    }
+    let set_count = error_sets.iter().map(|e| e.graph.len()).sum::<usize>();
+    if diagnostics.is_empty() {
+        println!("No problems found in {set_count} error sets");
+        true
+    } else {
+        eprintln!("Found {} problems", diagnostics.len());
+        false
+    }
+}
+
+fn combine(crate_dir: &Path) -> bool {
+    let result = error_set::CombineParts::new()
+        .scan(crate_path(crate_dir, "src"))
+        .output(crate_path(crate_dir, "src/error_set.rs"))
+        .rerun_if_changed(false)
+        .run();
+    match result {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("error: {error}");
+            false
+        }
+    }
+}
+
+fn migrate(crate_dir: &Path, write: bool) -> bool {
+    let mut is_success = true;
+    let mut set_count = 0;
+    for file in rust_files(crate_dir) {
+        let result = fs::read_to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if !content.contains("thiserror") {
+                    return Ok((content, Vec::new(), Vec::new()));
+                }
+                let (migrations, notes) =
+                    migrate_file(&file, &content).map_err(|e| e.to_string())?;
+                Ok((content, migrations, notes))
+            });
+        let (content, migrations, notes) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                eprintln!("error: {error}\n  --> {}\n", file.display());
+                is_success = false;
+                continue;
+            }
+        };
+        for note in &notes {
+            eprintln!("note: {note}\n");
+        }
+        if migrations.is_empty() {
+            continue;
+        }
+        set_count += migrations.iter().map(Migration::set_count).sum::<usize>();
+        if write {
+            if let Err(error) = fs::write(&file, rewrite(&content, &migrations)) {
+                eprintln!("error: {error}\n  --> {}\n", file.display());
+                is_success = false;
+            }
+            continue;
+        }
+        for migration in &migrations {
+            if migration.module.is_empty() {
+                println!("// {}", file.display());
+            } else {
+                println!(
+                    "// {}, in `mod {}`",
+                    file.display(),
+                    migration.module.join("::")
+                );
+            }
+            println!("{}", migration.render());
+        }
+    }
+    if write {
+        println!("Migrated {set_count} enums");
+    } else {
+        println!("Found {set_count} enums to migrate, replace them with `--write`");
+    }
+    is_success
+}
+
+/// Prints each resolved error set with [print], and any problems found
+fn print_error_sets(crate_dir: &Path, print: impl Fn(&ResolvedErrorSet)) -> bool {
+    let (error_sets, diagnostics) = load(crate_dir);
+    for (index, error_set) in error_sets.iter().enumerate() {
+        if index != 0 {
+            println!();
+        }
+        println!("{}", error_set.label);
+        print(error_set);
+    }
+    for diagnostic in &diagnostics {
+        eprintln!("\n{diagnostic}");
+    }
+    diagnostics.is_empty()
+}
+
+fn generics_to_string(generics: &[syn::TypeParam]) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+    let generics = generics
+        .iter()
+        .map(tokens_to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("<{generics}>")
+}
@@ -0,0 +1,788 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+
+use proc_macro2::{LineColumn, TokenStream, TokenTree};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Fields, GenericParam, Item, ItemEnum, LitStr, Meta, Token, Variant, Visibility,
+};
+
+use error_set_core::ast::AstErrorSet;
+use error_set_core::resolve_error_set;
+
+const INDENT: &str = "    ";
+
+/// The `thiserror` enums of one module of a file, translated into a single `error_set!`
+pub(crate) struct Migration {
+    /// e.g. `["inner"]` for enums in `mod inner { .. }`, empty for the top level of the file
+    pub(crate) module: Vec<String>,
+    sets: Vec<MigratedSet>,
+}
+
+/// A `thiserror` enum translated into an error set declaration
+struct MigratedSet {
+    name: String,
+    line: usize,
+    /// Byte range of the enum in the file, including its attributes
+    range: Range<usize>,
+    /// e.g. doc comments and derives other than `Debug` and `Error`, as written
+    attributes: Vec<String>,
+    /// Only restricted visibilities, e.g. `pub(crate)`, since `pub` is the default for error sets
+    vis: Option<String>,
+    generics: String,
+    /// `Debug` was not derived, so is implemented elsewhere
+    skip_debug: bool,
+    /// Source types that were not `#[from]`, so `thiserror` did not convert from them
+    skip_froms: Vec<String>,
+    /// Each variant as written in the declaration, without indentation
+    variants: Vec<String>,
+    /// `(variant name, source type)`
+    source_types: Vec<(String, String)>,
+    /// Sets whose variants are all in this set, written with `||`
+    refs: Vec<String>,
+}
+
+/// A variant of a `thiserror` enum translated into an error set variant
+struct MigratedVariant {
+    text: String,
+    source_type: Option<String>,
+    is_from: bool,
+}
+
+/// What `#[error(..)]` translates to
+enum Display {
+    /// `#[error(transparent)]`, which is the default for source variants
+    Transparent,
+    /// The arguments of `#[display(..)]`
+    Format(String),
+}
+
+impl Migration {
+    pub(crate) fn set_count(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// The `error_set!` invocation, not indented
+    pub(crate) fn render(&self) -> String {
+        format!("error_set::error_set! {{\n{}}}\n", self.body())
+    }
+
+    fn body(&self) -> String {
+        let mut body = String::new();
+        for (index, set) in self.sets.iter().enumerate() {
+            if index != 0 {
+                body.push('\n');
+            }
+            set.render(&mut body);
+        }
+        body
+    }
+}
+
+impl MigratedSet {
+    fn render(&self, output: &mut String) {
+        for attribute in &self.attributes {
+            output.push_str(&format!("{INDENT}{attribute}\n"));
+        }
+        let mut skips = Vec::new();
+        if self.skip_debug {
+            skips.push("Debug".to_string());
+        }
+        if !self.skip_froms.is_empty() {
+            skips.push(format!("From({})", self.skip_froms.join(", ")));
+        }
+        if !skips.is_empty() {
+            output.push_str(&format!("{INDENT}#[skip({})]\n", skips.join(", ")));
+        }
+        let vis = match &self.vis {
+            Some(vis) => format!("{vis} "),
+            None => String::new(),
+        };
+        let mut parts = self.refs.clone();
+        if !self.variants.is_empty() || parts.is_empty() {
+            parts.push("{".to_string());
+        }
+        output.push_str(&format!(
+            "{INDENT}{vis}{}{} := {}\n",
+            self.name,
+            self.generics,
+            parts.join(" || ")
+        ));
+        if parts.last().is_some_and(|e| e == "{") {
+            for variant in &self.variants {
+                for line in variant.lines() {
+                    output.push_str(&format!("{INDENT}{INDENT}{line}\n"));
+                }
+            }
+            output.push_str(&format!("{INDENT}}}\n"));
+        }
+    }
+}
+
+/// Translates the `thiserror` enums of [content] into `error_set!` declarations, one [Migration] per module. Enums
+/// that cannot be translated are left as they are, and noted with why.
+pub(crate) fn migrate_file(
+    file: &Path,
+    content: &str,
+) -> syn::Result<(Vec<Migration>, Vec<String>)> {
+    let parsed_file = syn::parse_file(content)?;
+    let source = Source::new(content);
+    let is_thiserror_imported = content.contains("thiserror");
+    let mut migrations = Vec::new();
+    let mut notes = Vec::new();
+    let mut modules = vec![(Vec::new(), &parsed_file.items)];
+    let mut index = 0;
+    while index < modules.len() {
+        let (module, items) = modules[index].clone();
+        index += 1;
+        let mut sets = Vec::new();
+        for item in items {
+            match item {
+                Item::Mod(item_mod) => {
+                    if let Some((_, items)) = &item_mod.content {
+                        let mut module = module.clone();
+                        module.push(item_mod.ident.to_string());
+                        modules.push((module, items));
+                    }
+                }
+                Item::Enum(item_enum)
+                    if is_thiserror_derived(&item_enum.attrs, is_thiserror_imported) =>
+                {
+                    match migrate_enum(&source, item_enum) {
+                        Ok(set) => sets.push(set),
+                        Err(reason) => notes.push(format!(
+                            "{}:{}: `{}` was not migrated, {reason}",
+                            file.display(),
+                            item_enum.ident.span().start().line,
+                            item_enum.ident
+                        )),
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(first) = sets.first() else {
+            continue;
+        };
+        let line = first.line;
+        find_supersets(&mut sets);
+        let names = sets.iter().map(|e| e.name.as_str()).collect::<HashSet<_>>();
+        for set in &sets {
+            for (variant_name, source_type) in &set.source_types {
+                if names.contains(source_type.as_str()) {
+                    notes.push(format!(
+                        "{}:{}: `{}::{variant_name}` wraps `{source_type}`. Declaring `{} := {source_type} || {{ .. }}` instead \
+                         puts the variants of `{source_type}` directly in `{}`, and `?` still converts between them",
+                        file.display(),
+                        set.line,
+                        set.name,
+                        set.name,
+                        set.name
+                    ));
+                }
+            }
+        }
+        let migration = Migration { module, sets };
+        // Dev Note: Resolved the same way as the macro, so a translation that would not compile is never written
+        let resolved = syn::parse_str::<AstErrorSet>(&migration.body()).and_then(resolve_error_set);
+        if let Err(error) = resolved {
+            notes.push(format!(
+                "{}:{line}: the enums of this module were not migrated, the translated `error_set!` is invalid - {error}",
+                file.display()
+            ));
+            continue;
+        }
+        migrations.push(migration);
+    }
+    Ok((migrations, notes))
+}
+
+/// [content] with the enums of each migration replaced by its `error_set!`, written where the first enum was
+pub(crate) fn rewrite(content: &str, migrations: &[Migration]) -> String {
+    let mut replacements = Vec::new();
+    for migration in migrations {
+        let rendered = migration.render();
+        for (index, set) in migration.sets.iter().enumerate() {
+            let mut range = set.range.clone();
+            // Whole lines, so the indentation and line break of the enum are replaced too
+            let line_start = content[..range.start].rfind('\n').map_or(0, |e| e + 1);
+            if content[line_start..range.start].trim().is_empty() {
+                range.start = line_start;
+            }
+            let rest_of_line = content[range.end..]
+                .find('\n')
+                .map_or(content.len(), |e| range.end + e + 1);
+            if content[range.end..rest_of_line].trim().is_empty() {
+                range.end = rest_of_line;
+            }
+            if index == 0 {
+                let indent = &content[range.start..set.range.start];
+                let replacement = rendered
+                    .lines()
+                    .map(|e| format!("{indent}{e}\n"))
+                    .collect::<String>();
+                replacements.push((range, replacement));
+            } else {
+                // The blank line that separated it from the next item
+                if content[range.end..].starts_with('\n') {
+                    range.end += 1;
+                }
+                replacements.push((range, String::new()));
+            }
+        }
+    }
+    replacements.sort_by_key(|(range, _)| range.start);
+    let mut output = String::new();
+    let mut last = 0;
+    for (range, replacement) in replacements {
+        output.push_str(&content[last..range.start]);
+        output.push_str(&replacement);
+        last = range.end;
+    }
+    output.push_str(&content[last..]);
+    output
+}
+
+/// Writes sets that contain all the variants of other sets as `Other || { .. }`. Generic sets are left as they
+/// are, and of sets with the same variants, only the later declared references the earlier.
+fn find_supersets(sets: &mut [MigratedSet]) {
+    let mut all_refs = Vec::new();
+    for index in 0..sets.len() {
+        let set = &sets[index];
+        let mut subsets = (0..sets.len())
+            .filter(|&other| {
+                other != index
+                    && set.generics.is_empty()
+                    && sets[other].generics.is_empty()
+                    && !sets[other].variants.is_empty()
+                    && sets[other]
+                        .variants
+                        .iter()
+                        .all(|e| set.variants.contains(e))
+                    && (sets[other].variants.len() < set.variants.len() || other < index)
+            })
+            .collect::<Vec<_>>();
+        subsets.sort_by_key(|&other| Reverse(sets[other].variants.len()));
+        let mut covered = HashSet::new();
+        let mut refs = Vec::new();
+        for other in subsets {
+            if sets[other].variants.iter().any(|e| !covered.contains(e)) {
+                covered.extend(sets[other].variants.iter().cloned());
+                refs.push(sets[other].name.clone());
+            }
+        }
+        all_refs.push((refs, covered));
+    }
+    for (set, (refs, covered)) in sets.iter_mut().zip(all_refs) {
+        set.variants.retain(|e| !covered.contains(e));
+        set.refs = refs;
+    }
+}
+
+fn is_thiserror_derived(attributes: &[Attribute], is_thiserror_imported: bool) -> bool {
+    attributes.iter().any(|attribute| {
+        attribute.path().is_ident("derive")
+            && attribute
+                .parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .is_ok_and(|paths| {
+                    paths
+                        .iter()
+                        .any(|path| is_thiserror_error(path, is_thiserror_imported))
+                })
+    })
+}
+
+/// e.g. `thiserror::Error`, or `Error` when `thiserror` is imported
+fn is_thiserror_error(path: &syn::Path, is_thiserror_imported: bool) -> bool {
+    let segments = path
+        .segments
+        .iter()
+        .map(|e| e.ident.to_string())
+        .collect::<Vec<_>>();
+    segments == ["thiserror", "Error"] || (is_thiserror_imported && segments == ["Error"])
+}
+
+fn migrate_enum(source: &Source, item_enum: &ItemEnum) -> Result<MigratedSet, String> {
+    let generics = &item_enum.generics;
+    if generics.where_clause.is_some()
+        || generics
+            .params
+            .iter()
+            .any(|e| !matches!(e, GenericParam::Type(_)))
+    {
+        return Err(
+            "only generic type parameters without a `where` clause are supported".to_string(),
+        );
+    }
+    let mut attributes = Vec::new();
+    let mut skip_debug = true;
+    for attribute in &item_enum.attrs {
+        if attribute.path().is_ident("error") {
+            return Err("`#[error]` on the enum is not supported".to_string());
+        }
+        let derives = attribute
+            .path()
+            .is_ident("derive")
+            .then(|| {
+                attribute.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+            })
+            .and_then(Result::ok);
+        let Some(derives) = derives else {
+            attributes.push(source.text(attribute.span()).to_string());
+            continue;
+        };
+        let mut kept = Vec::new();
+        for derive in &derives {
+            if derive.is_ident("Debug") {
+                skip_debug = false;
+            } else if !is_thiserror_error(derive, true) {
+                kept.push(source.text(derive.span()));
+            }
+        }
+        if !kept.is_empty() {
+            attributes.push(format!("#[derive({})]", kept.join(", ")));
+        }
+    }
+    let mut variants = Vec::new();
+    let mut skip_froms = Vec::new();
+    let mut source_types = Vec::new();
+    for variant in &item_enum.variants {
+        let migrated = migrate_variant(source, variant)
+            .map_err(|reason| format!("the variant `{}` {reason}", variant.ident))?;
+        if let Some(source_type) = migrated.source_type {
+            if !migrated.is_from {
+                skip_froms.push(source_type.clone());
+            }
+            source_types.push((variant.ident.to_string(), source_type));
+        }
+        variants.push(migrated.text);
+    }
+    let range = source.offset(item_enum.span().start())..source.offset(item_enum.span().end());
+    Ok(MigratedSet {
+        name: item_enum.ident.to_string(),
+        line: item_enum.ident.span().start().line,
+        range,
+        attributes,
+        vis: match &item_enum.vis {
+            Visibility::Restricted(vis) => Some(source.text(vis.span()).to_string()),
+            _ => None,
+        },
+        generics: if generics.params.is_empty() {
+            String::new()
+        } else {
+            source.text(generics.span()).to_string()
+        },
+        skip_debug,
+        skip_froms,
+        variants,
+        source_types,
+        refs: Vec::new(),
+    })
+}
+
+fn migrate_variant(source: &Source, variant: &Variant) -> Result<MigratedVariant, String> {
+    if variant.discriminant.is_some() {
+        return Err("has a discriminant".to_string());
+    }
+    let error_attribute = variant.attrs.iter().find(|e| e.path().is_ident("error"));
+    let is_transparent = error_attribute.is_some_and(|e| {
+        e.parse_args::<syn::Ident>()
+            .is_ok_and(|e| e == "transparent")
+    });
+    let name = &variant.ident;
+    let (shape, source_type, is_from) = match &variant.fields {
+        Fields::Unit => (name.to_string(), None, false),
+        Fields::Unnamed(fields) => {
+            if fields.unnamed.len() != 1 {
+                return Err(
+                    "has more than one field, but tuple variants can only wrap a source error"
+                        .to_string(),
+                );
+            }
+            let field = &fields.unnamed[0];
+            let (is_from, is_source, attributes) = source_attributes(source, &field.attrs)?;
+            if !(is_from || is_source || is_transparent) {
+                return Err(
+                    "has a field that is not `#[from]` or `#[source]`, but tuple variants can only wrap a source error"
+                        .to_string(),
+                );
+            }
+            if !attributes.is_empty() {
+                return Err("has attributes on its field".to_string());
+            }
+            let source_type = source.text(field.ty.span()).to_string();
+            (format!("{name}({source_type})"), Some(source_type), is_from)
+        }
+        Fields::Named(fields) => {
+            let mut source_field = None;
+            let mut other_fields = Vec::new();
+            for field in &fields.named {
+                let ident = field.ident.as_ref().unwrap();
+                let (is_from, is_source, attributes) = source_attributes(source, &field.attrs)?;
+                let r#type = source.text(field.ty.span());
+                if is_from || is_source || ident == "source" {
+                    if ident != "source" {
+                        return Err(format!(
+                            "has the source field `{ident}`, but source fields are named `source`"
+                        ));
+                    }
+                    source_field = Some((r#type.to_string(), is_from));
+                } else {
+                    other_fields.push((attributes, format!("{ident}: {type}")));
+                }
+            }
+            let head = match &source_field {
+                Some((source_type, _)) => format!("{name}({source_type})"),
+                None => name.to_string(),
+            };
+            let shape = if other_fields.is_empty() {
+                format!("{head} {{}}")
+            } else if other_fields
+                .iter()
+                .all(|(attributes, _)| attributes.is_empty())
+            {
+                let fields = other_fields
+                    .into_iter()
+                    .map(|(_, field)| field)
+                    .collect::<Vec<_>>();
+                format!("{head} {{ {} }}", fields.join(", "))
+            } else {
+                let mut shape = format!("{head} {{\n");
+                for (attributes, field) in other_fields {
+                    for attribute in attributes {
+                        shape.push_str(&format!("{INDENT}{attribute}\n"));
+                    }
+                    shape.push_str(&format!("{INDENT}{field},\n"));
+                }
+                shape.push('}');
+                shape
+            };
+            match source_field {
+                Some((source_type, is_from)) => (shape, Some(source_type), is_from),
+                None => (shape, None, false),
+            }
+        }
+    };
+    let is_source_tuple = matches!(variant.fields, Fields::Unnamed(_));
+    let mut lines = Vec::new();
+    for attribute in &variant.attrs {
+        if !attribute.path().is_ident("error") {
+            lines.push(source.text(attribute.span()).to_string());
+            continue;
+        }
+        match error_display(source, attribute, is_source_tuple)? {
+            Display::Transparent if source_type.is_none() => {
+                return Err("is `#[error(transparent)]` without a source".to_string());
+            }
+            Display::Transparent => {}
+            Display::Format(args) => lines.push(format!("#[display({args})]")),
+        }
+    }
+    lines.push(format!("{shape},"));
+    Ok(MigratedVariant {
+        text: lines.join("\n"),
+        source_type,
+        is_from,
+    })
+}
+
+/// Whether a field is `#[from]` and `#[source]`, and its other attributes as written
+fn source_attributes(
+    source: &Source,
+    attributes: &[Attribute],
+) -> Result<(bool, bool, Vec<String>), String> {
+    let mut is_from = false;
+    let mut is_source = false;
+    let mut others = Vec::new();
+    for attribute in attributes {
+        if attribute.path().is_ident("from") {
+            is_from = true;
+        } else if attribute.path().is_ident("source") {
+            is_source = true;
+        } else if attribute.path().is_ident("backtrace") {
+            return Err("uses `#[backtrace]`, which is not supported".to_string());
+        } else {
+            others.push(source.text(attribute.span()).to_string());
+        }
+    }
+    Ok((is_from, is_source, others))
+}
+
+/// Translates `#[error(..)]`. Additional format arguments can refer to fields as `.field` or `.0` in `thiserror`,
+/// which are `field` and `source` in `#[display(..)]`.
+fn error_display(
+    source: &Source,
+    attribute: &Attribute,
+    is_source_tuple: bool,
+) -> Result<Display, String> {
+    let Meta::List(list) = &attribute.meta else {
+        return Err("has an `#[error]` without arguments".to_string());
+    };
+    let mut args = vec![Vec::new()];
+    for token in list.tokens.clone() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => args.push(Vec::new()),
+            _ => args.last_mut().unwrap().push(token),
+        }
+    }
+    if args.last().is_some_and(Vec::is_empty) {
+        args.pop();
+    }
+    let mut args = args.into_iter();
+    let format = args.next().unwrap_or_default();
+    let format = TokenStream::from_iter(format);
+    if args.len() == 0
+        && syn::parse2::<syn::Ident>(format.clone()).is_ok_and(|e| e == "transparent")
+    {
+        return Ok(Display::Transparent);
+    }
+    let Ok(format) = syn::parse2::<LitStr>(format) else {
+        return Err("has an `#[error]` that is not a format string or `transparent`".to_string());
+    };
+    let mut display_args = vec![source.text(format.span()).to_string()];
+    for arg in args {
+        let (Some(first), Some(last)) = (arg.first(), arg.last()) else {
+            continue;
+        };
+        let text =
+            &source.content[source.offset(first.span().start())..source.offset(last.span().end())];
+        let text = match text.strip_prefix('.') {
+            Some(field) if is_source_tuple && (field == "0" || field.starts_with("0.")) => {
+                format!("source{}", &field[1..])
+            }
+            Some(field) => field.to_string(),
+            None => text.to_string(),
+        };
+        display_args.push(text);
+    }
+    Ok(Display::Format(display_args.join(", ")))
+}
+
+/// The text of spans in a file, whose locations are lines and columns in chars
+struct Source<'a> {
+    content: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Source<'a> {
+    fn new(content: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(e, _)| e + 1))
+            .collect();
+        Source {
+            content,
+            line_starts,
+        }
+    }
+
+    fn offset(&self, location: LineColumn) -> usize {
+        let line_start = self.line_starts[location.line - 1];
+        self.content[line_start..]
+            .char_indices()
+            .nth(location.column)
+            .map_or(self.content.len(), |(e, _)| line_start + e)
+    }
+
+    fn text(&self, span: proc_macro2::Span) -> &'a str {
+        &self.content[self.offset(span.start())..self.offset(span.end())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate(content: &str) -> (Vec<Migration>, Vec<String>) {
+        migrate_file(Path::new("src/lib.rs"), content).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_translates_attributes() {
+        let (migrations, notes) = migrate(
+            r#"
+use thiserror::Error;
+
+/// Errors of downloads
+#[derive(Debug, Error, Clone)]
+pub enum DownloadError {
+    #[error("io failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("timed out after {secs}s for {}", .url)]
+    Timeout { secs: u64, url: String },
+    #[error(transparent)]
+    Fmt(#[source] std::fmt::Error),
+    #[error("parse failed at {line}")]
+    Parse {
+        source: std::num::ParseIntError,
+        line: usize,
+    },
+}
+"#,
+        );
+        assert!(notes.is_empty(), "{notes:?}");
+        assert_eq!(
+            migrations[0].render(),
+            r#"error_set::error_set! {
+    /// Errors of downloads
+    #[derive(Clone)]
+    #[skip(From(std::fmt::Error, std::num::ParseIntError))]
+    DownloadError := {
+        #[display("io failed: {0}")]
+        Io(std::io::Error),
+        #[display("timed out after {secs}s for {}", url)]
+        Timeout { secs: u64, url: String },
+        Fmt(std::fmt::Error),
+        #[display("parse failed at {line}")]
+        Parse(std::num::ParseIntError) { line: usize },
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_migrate_references_subsets() {
+        let (migrations, notes) = migrate(
+            r#"
+#[derive(Debug, thiserror::Error)]
+pub enum MediaError {
+    #[error("io failed")]
+    Io(#[from] std::io::Error),
+    #[error("timed out")]
+    Timeout,
+    #[error("invalid format")]
+    Format,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    #[error("io failed")]
+    Io(#[from] std::io::Error),
+    #[error("timed out")]
+    Timeout,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error("{0}")]
+    Download(#[from] DownloadError),
+}
+"#,
+        );
+        assert_eq!(migrations[0].set_count(), 3);
+        assert_eq!(
+            migrations[0].render(),
+            r#"error_set::error_set! {
+    MediaError := DownloadError || {
+        #[display("invalid format")]
+        Format,
+    }
+
+    DownloadError := {
+        #[display("io failed")]
+        Io(std::io::Error),
+        #[display("timed out")]
+        Timeout,
+    }
+
+    UploadError := {
+        #[display("{0}")]
+        Download(DownloadError),
+    }
+}
+"#
+        );
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("`UploadError::Download` wraps `DownloadError`"));
+    }
+
+    #[test]
+    fn test_migrate_notes_unsupported() {
+        let (migrations, notes) = migrate(
+            r#"
+#[derive(Debug, thiserror::Error)]
+enum A {
+    #[error("{0} and {1}")]
+    Pair(u32, u32),
+}
+
+#[derive(Debug, thiserror::Error)]
+enum B {
+    #[error("io")]
+    Io { #[source] inner: std::io::Error },
+}
+
+#[derive(Debug)]
+enum NotThiserror {
+    X,
+}
+"#,
+        );
+        assert!(migrations.is_empty());
+        assert_eq!(notes.len(), 2);
+        assert!(notes[0].starts_with(
+            "src/lib.rs:3: `A` was not migrated, the variant `Pair` has more than one field"
+        ));
+        assert!(notes[1].contains("the variant `Io` has the source field `inner`"));
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let content = r#"use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum A {
+    #[error("x")]
+    X,
+}
+
+#[derive(Debug, Error)]
+pub enum B {
+    #[error("x")]
+    X,
+    #[error("y")]
+    Y,
+}
+
+mod inner {
+    #[derive(Debug, thiserror::Error)]
+    pub(crate) enum C {
+        #[error("z")]
+        Z,
+    }
+}
+"#;
+        let (migrations, notes) = migrate(content);
+        assert!(notes.is_empty(), "{notes:?}");
+        assert_eq!(migrations[1].module, ["inner"]);
+        assert_eq!(
+            rewrite(content, &migrations),
+            r#"use thiserror::Error;
+
+error_set::error_set! {
+    A := {
+        #[display("x")]
+        X,
+    }
+
+    B := A || {
+        #[display("y")]
+        Y,
+    }
+}
+
+mod inner {
+    error_set::error_set! {
+        pub(crate) C := {
+            #[display("z")]
+            Z,
+        }
+    }
+}
+"#
+        );
+    }
+}
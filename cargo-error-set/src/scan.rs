@@ -0,0 +1,285 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+use proc_macro2::Span;
+use syn::visit::Visit;
+
+use error_set_core::ast::{AstErrorSet, AstErrorSetPart, AstErrorStruct};
+use error_set_core::graph::ErrorEnumGraphNode;
+use error_set_core::resolve_error_set;
+
+const ERROR_SET_MACRO_NAME: &str = "error_set";
+const PART_MACRO_NAME: &str = "error_set_part";
+// Dev Note: Written by `combine_parts`, its sets are already found in the parts
+const GENERATED_HEADER: &str = "// This file is auto-generated";
+
+/// The sets of one `error_set!`, or of all `error_set_part!`s combined, after resolving
+pub(crate) struct ResolvedErrorSet {
+    /// Where the sets were declared, e.g. `error_set! at src/lib.rs:3`
+    pub(crate) label: String,
+    pub(crate) graph: Vec<ErrorEnumGraphNode>,
+    pub(crate) error_structs: Vec<AstErrorStruct>,
+}
+
+/// A problem found while scanning, pointing to where it occurred
+pub(crate) struct Diagnostic {
+    file: PathBuf,
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error: {}\n  --> {}:{}:{}",
+            self.message,
+            self.file.display(),
+            self.line,
+            self.column
+        )
+    }
+}
+
+/// Finds, parses, resolves and validates every `error_set!` and `error_set_part!` in the `src` of the crate at
+/// [crate_dir], the same as the macros would.
+pub(crate) fn load(crate_dir: &Path) -> (Vec<ResolvedErrorSet>, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    let mut invocations = Vec::new();
+    for file in rust_files(crate_dir) {
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(error) => {
+                diagnostics.push(Diagnostic {
+                    file,
+                    line: 1,
+                    column: 1,
+                    message: error.to_string(),
+                });
+                continue;
+            }
+        };
+        if let Err(error) = find_invocations(&content, &file, &mut invocations) {
+            diagnostics.extend(to_diagnostics(error, &[], &file, 1));
+        }
+    }
+
+    let (parts, error_sets): (Vec<_>, Vec<_>) = invocations.into_iter().partition(|e| e.is_part);
+    let mut units = error_sets
+        .into_iter()
+        .map(|e| {
+            (
+                format!("error_set! at {}:{}", e.file.display(), e.line),
+                vec![e],
+            )
+        })
+        .collect::<Vec<_>>();
+    if !parts.is_empty() {
+        units.push((format!("error_set_part! ({} combined)", parts.len()), parts));
+    }
+
+    let mut resolved_error_sets = Vec::new();
+    for (label, invocations) in units {
+        match resolve_unit(&invocations) {
+            Ok((graph, error_structs)) => resolved_error_sets.push(ResolvedErrorSet {
+                label,
+                graph,
+                error_structs,
+            }),
+            Err(error) => {
+                let first = &invocations[0];
+                diagnostics.extend(to_diagnostics(error, &invocations, &first.file, first.line));
+            }
+        }
+    }
+    (resolved_error_sets, diagnostics)
+}
+
+/// The `.rs` files in the `src` of the crate at [crate_dir], sorted
+pub(crate) fn rust_files(crate_dir: &Path) -> Vec<PathBuf> {
+    let mut files = WalkBuilder::new(crate_path(crate_dir, "src"))
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("rs"))
+        .map(|e| e.path().to_path_buf())
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Where files of the crate are, without a `./` prefix for the current directory so paths match those written
+/// by `build.rs`
+pub(crate) fn crate_path(crate_dir: &Path, path: &str) -> PathBuf {
+    if crate_dir == Path::new(".") {
+        PathBuf::from(path)
+    } else {
+        crate_dir.join(path)
+    }
+}
+
+/// An `error_set!` or `error_set_part!`
+struct Invocation {
+    file: PathBuf,
+    line: usize,
+    is_part: bool,
+    /// Spans the whole macro body, to find which invocation an error points into
+    span: Span,
+    tokens: proc_macro2::TokenStream,
+}
+
+fn find_invocations(
+    content: &str,
+    file: &Path,
+    invocations: &mut Vec<Invocation>,
+) -> syn::Result<()> {
+    if content.starts_with(GENERATED_HEADER)
+        || !(content.contains(ERROR_SET_MACRO_NAME) || content.contains(PART_MACRO_NAME))
+    {
+        return Ok(());
+    }
+    let parsed_file = syn::parse_file(content)?;
+    let mut visitor = MacroVisitor { macros: Vec::new() };
+    visitor.visit_file(&parsed_file);
+    for mac in visitor.macros {
+        let name = &mac.path.segments.last().unwrap().ident;
+        let delimiter_span = match &mac.delimiter {
+            syn::MacroDelimiter::Paren(e) => e.span.join(),
+            syn::MacroDelimiter::Brace(e) => e.span.join(),
+            syn::MacroDelimiter::Bracket(e) => e.span.join(),
+        };
+        invocations.push(Invocation {
+            file: file.to_path_buf(),
+            line: name.span().start().line,
+            is_part: name == PART_MACRO_NAME,
+            span: delimiter_span,
+            tokens: mac.tokens.clone(),
+        });
+    }
+    Ok(())
+}
+
+struct MacroVisitor<'ast> {
+    macros: Vec<&'ast syn::Macro>,
+}
+
+impl<'ast> Visit<'ast> for MacroVisitor<'ast> {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        // e.g. `error_set!` or `error_set::error_set!`
+        if mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|e| e.ident == ERROR_SET_MACRO_NAME || e.ident == PART_MACRO_NAME)
+        {
+            self.macros.push(mac);
+        }
+        syn::visit::visit_macro(self, mac);
+    }
+}
+
+fn resolve_unit(
+    invocations: &[Invocation],
+) -> syn::Result<(Vec<ErrorEnumGraphNode>, Vec<AstErrorStruct>)> {
+    let mut set_items = Vec::new();
+    for invocation in invocations {
+        if invocation.is_part {
+            let error_set_part = syn::parse2::<AstErrorSetPart>(invocation.tokens.clone())?;
+            set_items.extend(error_set_part.error_set.set_items);
+        } else {
+            set_items.extend(syn::parse2::<AstErrorSet>(invocation.tokens.clone())?.set_items);
+        }
+    }
+    let error_set = resolve_error_set(AstErrorSet { set_items })?;
+    Ok((error_set.graph, error_set.error_structs))
+}
+
+/// Locates each error in the invocation its span is in. Errors without a real location, e.g. the macro call site,
+/// point to the start of the first invocation.
+fn to_diagnostics(
+    error: syn::Error,
+    invocations: &[Invocation],
+    default_file: &Path,
+    default_line: usize,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for error in error {
+        let span = error.span();
+        let start = span.start();
+        // Dev Note: Spans of different files can only be joined if they are in the same file
+        let invocation = invocations.iter().find(|e| {
+            e.span.join(span).is_some()
+                && (e.span.start().line..=e.span.end().line).contains(&start.line)
+        });
+        let (file, line, column) = match invocation {
+            Some(invocation) => (invocation.file.clone(), start.line, start.column + 1),
+            None if invocations.is_empty() && start.line != 0 => {
+                (default_file.to_path_buf(), start.line, start.column + 1)
+            }
+            None => (default_file.to_path_buf(), default_line, 1),
+        };
+        diagnostics.push(Diagnostic {
+            file,
+            line,
+            column,
+            message: error.to_string(),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("cargo_error_set_{name}_{}", std::process::id()));
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_load_combines_parts() {
+        let dir = write_crate(
+            "parts",
+            &[
+                ("src/a.rs", "error_set_part! { A := { X } }"),
+                ("src/b.rs", "error_set_part! { B := A || { Y } }"),
+                (
+                    "src/error_set.rs",
+                    "// This file is auto-generated\n\nerror_set::error_set! { A := { X } }",
+                ),
+            ],
+        );
+        let (error_sets, diagnostics) = load(&dir);
+        assert!(diagnostics.is_empty());
+        assert_eq!(error_sets.len(), 1);
+        assert_eq!(error_sets[0].graph.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_locates_errors_in_parts() {
+        let dir = write_crate(
+            "errors",
+            &[
+                ("src/a.rs", "error_set_part! { A := { X } }"),
+                ("src/b.rs", "\n\nerror_set_part! {\n    B := Aa || { Y }\n}"),
+            ],
+        );
+        let (_, diagnostics) = load(&dir);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert!(diagnostic.file.ends_with("src/b.rs"));
+        assert_eq!((diagnostic.line, diagnostic.column), (4, 10));
+        assert!(diagnostic.message.contains("Did you mean `A`?"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
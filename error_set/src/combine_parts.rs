@@ -1,37 +1,251 @@
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use error_set_core::ast::AstErrorSetPart;
 use ignore::WalkBuilder;
+use proc_macro2::{Delimiter, Spacing, TokenTree};
+use syn::visit::Visit;
 
 /// Combines all uses of the `error_set_part!` macro in `src/` (respecting `.gitignore`) into one
-/// `error_set!` macro at the generated file `src/error_set.rs`
+/// `error_set!` macro at the generated file `src/error_set.rs`. Panics with the file and line of the problem if this
+/// fails, which fails the build. Use `CombineParts::new().run()` to handle the error instead.
 pub fn combine_error_set_parts() {
-    let mut parts = String::with_capacity(1024);
-    for file_path in find_rust_files("src") {
-        if let Ok(content) = fs::read_to_string(&file_path) {
-            extract_error_set_parts(&content, &mut parts, &file_path);
+    if let Err(error) = CombineParts::new().run() {
+        panic!("{error}");
+    }
+}
+
+/// Configures where `error_set_part!` invocations are found and where the combined `error_set!` is written.
+/// ```rust,ignore
+/// error_set::CombineParts::new()
+///     .scan("src")
+///     .scan("generated")
+///     .output_module("errors")
+///     .run()?;
+/// ```
+/// Parts must then import from the same module, with `#![module(crate::errors)]` as the first line of each
+/// `error_set_part!`.
+pub struct CombineParts {
+    scan_roots: Vec<ScanRoot>,
+    output: Output,
+    rerun_if_changed: bool,
+}
+
+struct ScanRoot {
+    path: PathBuf,
+    /// The workspace member the parts belong to, if not the current crate
+    crate_name: Option<String>,
+}
+
+enum Output {
+    Path(PathBuf),
+    /// File name relative to `$OUT_DIR`
+    OutDir(PathBuf),
+}
+
+impl CombineParts {
+    /// Scans `src` and writes to `src/error_set.rs` unless configured otherwise.
+    pub fn new() -> Self {
+        CombineParts {
+            scan_roots: Vec::new(),
+            output: Output::Path(PathBuf::from("src/error_set.rs")),
+            rerun_if_changed: true,
         }
     }
-    if parts.is_empty() {
-        return;
+
+    /// Adds a directory to search for `error_set_part!` invocations (respecting `.gitignore`). If never called,
+    /// `src` is scanned.
+    pub fn scan(mut self, root: impl Into<PathBuf>) -> Self {
+        self.scan_roots.push(ScanRoot {
+            path: root.into(),
+            crate_name: None,
+        });
+        self
     }
-    let output_path = "src/error_set.rs";
-    if let Err(e) = fs::write(
-        output_path,
-        format!(
-            "// This file is auto-generated\n\nerror_set::error_set! {{\n{parts}\n}}"
-        ),
-    ) {
-        panic!("Failed to write to {}: {}", output_path, e);
+
+    /// Adds the `src` directory of another workspace member crate, e.g. `.member("auth", "../auth")`, so parts
+    /// spread across a workspace are combined into one shared errors crate. The generated output records which
+    /// crate each part came from. Parts in other crates import from the shared crate, e.g.
+    /// `#![module(shared_errors)]`.
+    pub fn member(mut self, crate_name: impl Into<String>, crate_dir: impl Into<PathBuf>) -> Self {
+        self.scan_roots.push(ScanRoot {
+            path: crate_dir.into().join("src"),
+            crate_name: Some(crate_name.into()),
+        });
+        self
+    }
+
+    /// Writes the combined `error_set!` to the file for the module at path `crate::<module>`, e.g. `"errors"` writes
+    /// to `src/errors.rs` and `"a::errors"` to `src/a/errors.rs`. Parts should declare `#![module(crate::<module>)]`.
+    pub fn output_module(mut self, module: &str) -> Self {
+        let mut output_path = PathBuf::from("src");
+        for segment in module.split("::") {
+            output_path.push(segment.trim());
+        }
+        output_path.set_extension("rs");
+        self.output = Output::Path(output_path);
+        self
+    }
+
+    /// Writes the combined `error_set!` to this file.
+    pub fn output(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output = Output::Path(path.into());
+        self
+    }
+
+    /// Writes the combined `error_set!` to `$OUT_DIR/<file_name>` instead of the source tree, so builds do not
+    /// modify `src`. The file is always written, even without parts, and is consumed with
+    /// ```rust,ignore
+    /// mod errors {
+    ///     include!(concat!(env!("OUT_DIR"), "/error_set.rs"));
+    /// }
+    /// ```
+    /// Parts should then declare `#![module(crate::errors)]`.
+    pub fn out_dir(mut self, file_name: impl Into<PathBuf>) -> Self {
+        self.output = Output::OutDir(file_name.into());
+        self
+    }
+
+    /// Whether to print `cargo:rerun-if-changed` for every scan root and scanned file, so `build.rs` only reruns
+    /// when parts may have changed. Enabled by default, disable when not running in a build script.
+    pub fn rerun_if_changed(mut self, rerun_if_changed: bool) -> Self {
+        self.rerun_if_changed = rerun_if_changed;
+        self
+    }
+
+    /// Combines the parts and writes the output file. The file is only rewritten if its content changed. Nothing is
+    /// written to the source tree if there are no parts.
+    pub fn run(self) -> Result<(), CombinePartsError> {
+        let output_path = match &self.output {
+            Output::Path(path) => path.clone(),
+            Output::OutDir(file_name) => match std::env::var_os("OUT_DIR") {
+                Some(out_dir) => PathBuf::from(out_dir).join(file_name),
+                None => {
+                    return Err(CombinePartsError::new(
+                        file_name,
+                        None,
+                        "`OUT_DIR` is not set, writing to it is only possible from a build script"
+                            .to_string(),
+                    ));
+                }
+            },
+        };
+        let scan_roots = if self.scan_roots.is_empty() {
+            vec![ScanRoot {
+                path: PathBuf::from("src"),
+                crate_name: None,
+            }]
+        } else {
+            self.scan_roots
+        };
+        let mut files = Vec::new();
+        for ScanRoot {
+            path: scan_root,
+            crate_name,
+        } in &scan_roots
+        {
+            if !scan_root.is_dir() {
+                return Err(CombinePartsError::new(
+                    scan_root,
+                    None,
+                    "Not a directory that can be scanned".to_string(),
+                ));
+            }
+            if self.rerun_if_changed {
+                // Dev Note: The directory itself so added files are noticed
+                println!("cargo:rerun-if-changed={}", scan_root.display());
+            }
+            for file_path in find_rust_files(scan_root) {
+                if file_path != output_path {
+                    files.push((file_path, crate_name.as_deref()));
+                }
+            }
+        }
+        // Dev Note: Walk order differs between machines, sorting keeps the output stable in version control
+        files.sort_by(|(this, _), (that, _)| this.cmp(that));
+        files.dedup_by(|(this, _), (that, _)| this == that);
+        let mut parts = Vec::new();
+        for (file_path, crate_name) in files {
+            if self.rerun_if_changed {
+                println!("cargo:rerun-if-changed={}", file_path.display());
+            }
+            let content = fs::read_to_string(&file_path)
+                .map_err(|e| CombinePartsError::new(&file_path, None, e.to_string()))?;
+            extract_error_set_parts(&content, &mut parts, &file_path, crate_name)?;
+        }
+        if parts.is_empty() && matches!(self.output, Output::Path(_)) {
+            return Ok(());
+        }
+        let output = render(&parts);
+        // Dev Note: Rewriting unchanged output would update its mtime and trigger needless rebuilds
+        if fs::read_to_string(&output_path).is_ok_and(|existing| existing == output) {
+            return Ok(());
+        }
+        fs::write(&output_path, output).map_err(|e| {
+            CombinePartsError::new(&output_path, None, format!("Failed to write: {e}"))
+        })?;
+        Ok(())
+    }
+}
+
+impl Default for CombineParts {
+    fn default() -> Self {
+        Self::new()
     }
-    // if let Err(e) = std::process::Command::new("rustfmt")
-    //     .arg(output_path)
-    //     .status()
-    // {
-    //     println!("cargo:warning=Failed to format {:?}: {}", output_path, e);
-    // }
 }
 
+/// An error encountered while combining `error_set_part!` invocations, pointing to where it occurred.
+pub struct CombinePartsError {
+    path: PathBuf,
+    /// 1-based line and column
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+impl CombinePartsError {
+    fn new(path: &Path, location: Option<(usize, usize)>, message: String) -> Self {
+        CombinePartsError {
+            path: path.to_path_buf(),
+            location,
+            message,
+        }
+    }
+
+    /// The file the error occurred in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The 1-based line of the error in [Self::path], if known
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+}
+
+impl fmt::Display for CombinePartsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(
+                f,
+                "{}:{line}:{column}: {}",
+                self.path.display(),
+                self.message
+            ),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+// Dev Note: Same as display, so `fn main() -> Result<(), CombinePartsError>` in `build.rs` reports the location
+impl fmt::Debug for CombinePartsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for CombinePartsError {}
+
 #[inline]
 fn find_rust_files<P: AsRef<Path>>(dir: P) -> impl Iterator<Item = PathBuf> {
     WalkBuilder::new(dir)
@@ -41,69 +255,376 @@ fn find_rust_files<P: AsRef<Path>>(dir: P) -> impl Iterator<Item = PathBuf> {
         .map(|e| e.path().to_path_buf())
 }
 
-#[inline]
-fn extract_error_set_parts(content: &str, error_set_body: &mut String, current_file: &Path) {
-    let needle = "error_set_part!";
-    let mut pos = 0;
-    
-    while let Some(relative_idx) = content[pos..].find(needle) {
-        let statement_start = pos + relative_idx;
-        let mut brace_pos = statement_start + needle.len();
-        
-        // Skip whitespace
-        let skip = content[brace_pos..].chars()
-            .take_while(|c| c.is_whitespace())
-            .map(|c| c.len_utf8())
-            .sum::<usize>();
-        brace_pos += skip;
-        
-        if brace_pos < content.len() {
-            let end = extract_balanced_braces(content, brace_pos, error_set_body, current_file);
-            pos = end;
-        } else {
-            break;
+/// The body of an `error_set_part!` and where it was found
+struct Part {
+    file: PathBuf,
+    crate_name: Option<String>,
+    line: usize,
+    /// Indented by one level, see [format_items]
+    body: String,
+}
+
+/// The combined `error_set!`, with parts in the order found
+fn render(parts: &[Part]) -> String {
+    let mut output = String::from("// This file is auto-generated\n\nerror_set::error_set! {\n");
+    for (index, part) in parts.iter().enumerate() {
+        if index != 0 {
+            output.push('\n');
+        }
+        match &part.crate_name {
+            Some(crate_name) => output.push_str(&format!(
+                "{INDENT}// From `{crate_name}` at `{}:{}`\n",
+                part.file.display(),
+                part.line
+            )),
+            None => output.push_str(&format!(
+                "{INDENT}// From `{}:{}`\n",
+                part.file.display(),
+                part.line
+            )),
         }
+        output.push_str(&part.body);
     }
+    output.push_str("}\n");
+    output
 }
 
-#[inline]
-fn extract_balanced_braces(content: &str, brace_start_pos: usize, error_set_body: &mut String, current_file: &Path) -> usize {
-    let mut chars = content[brace_start_pos..].chars();
+const INDENT: &str = "    ";
 
-    let next = chars.next();
-    if let Some(next) = next {
-        if next != '{' {
-            panic!("Expected '{{' after error_set_part! macro in {}", current_file.display());
+/// Finds the `error_set_part!` invocations in the parsed file - not matches inside comments or string literals.
+fn extract_error_set_parts(
+    content: &str,
+    parts: &mut Vec<Part>,
+    current_file: &Path,
+    current_crate: Option<&str>,
+) -> Result<(), CombinePartsError> {
+    // Dev Note: Only fail on files that may contain a part. Other syntax errors are better reported by rustc.
+    if !content.contains(PART_MACRO_NAME) {
+        return Ok(());
+    }
+    let file = syn::parse_file(content).map_err(|e| {
+        let start = e.span().start();
+        CombinePartsError::new(
+            current_file,
+            Some((start.line, start.column + 1)),
+            format!("Failed to parse file: {e}"),
+        )
+    })?;
+    let mut visitor = PartMacroVisitor { parts: Vec::new() };
+    visitor.visit_file(&file);
+
+    for part in visitor.parts {
+        let start = part.path.segments.first().unwrap().ident.span().start();
+        if !matches!(part.delimiter, syn::MacroDelimiter::Brace(_)) {
+            return Err(CombinePartsError::new(
+                current_file,
+                Some((start.line, start.column + 1)),
+                format!("Expected '{{' after {PART_MACRO_NAME}! macro"),
+            ));
         }
-    } else {
-        panic!("Unexpected end of input after error_set_part! macro in {}", current_file.display());
+        // Skip the `#![module(..)]` that is only for the part itself. Dev Note: Other mistakes in the part are
+        // better reported by rustc where the part is.
+        let module_attribute = syn::parse::Parser::parse2(
+            |input: syn::parse::ParseStream| {
+                let module_attribute = AstErrorSetPart::parse_module_attribute(input)?;
+                input.parse::<proc_macro2::TokenStream>()?;
+                Ok(module_attribute)
+            },
+            part.tokens.clone(),
+        )
+        .ok()
+        .flatten();
+        let tokens: Vec<TokenTree> = part.tokens.clone().into_iter().collect();
+        // Dev Note: `#`, `!` and the bracket group
+        let body_start = if module_attribute.is_some() { 3 } else { 0 };
+        let body = format_items(&tokens[body_start..], 1);
+        parts.push(Part {
+            file: current_file.to_path_buf(),
+            crate_name: current_crate.map(str::to_string),
+            line: start.line,
+            body,
+        });
     }
+    Ok(())
+}
+
+const PART_MACRO_NAME: &str = "error_set_part";
 
-    let mut depth = 1;
-    let mut end = brace_start_pos + 1;
+/// Formats the items of a part body, one per line and indented by [depth] levels. Set and struct bodies have one
+/// variant or field per line. Doc comments are kept, other comments are not, since they are not tokens.
+fn format_items(tokens: &[TokenTree], depth: usize) -> String {
+    let mut formatted = String::new();
+    let mut rest = tokens;
+    while !rest.is_empty() {
+        let (attributes, item) = split_attributes(rest);
+        for attribute in attributes {
+            push_line(&mut formatted, depth, &format_attribute(attribute, depth));
+        }
+        let (item, next) = item.split_at(item_end(item));
+        push_line(&mut formatted, depth, &format_tokens(item, depth, true));
+        rest = next;
+    }
+    formatted
+}
 
-    for (i, ch) in chars.enumerate() {
-        match ch {
-            '{' => depth += 1,
-            '}' => {
-                depth -= 1;
-                if depth == 0 {
-                    end = brace_start_pos + i + 2; // +1 for opening brace, +1 for current closing brace
-                    break;
+/// The end of the item at the start of [tokens], e.g. `A := { B } || C` or `struct A { b: B }`
+fn item_end(tokens: &[TokenTree]) -> usize {
+    let mut index = 0;
+    loop {
+        match tokens.get(index) {
+            None => return index,
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                return index + 1;
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => return index + 1,
+            Some(_) if is_joint(tokens, index, ':', '=') => {
+                index += 2;
+                break;
+            }
+            Some(_) => index += 1,
+        }
+    }
+    // Dev Note: Each operand is a set body or a path to a set, e.g. `crate::A<T>`
+    loop {
+        match tokens.get(index) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => index += 1,
+            Some(TokenTree::Ident(_)) => {
+                index += 1;
+                loop {
+                    if is_joint(tokens, index, ':', ':') {
+                        index += 3;
+                    } else if matches!(tokens.get(index), Some(TokenTree::Punct(punct)) if punct.as_char() == '<')
+                    {
+                        let mut angle_depth = 0;
+                        while let Some(token) = tokens.get(index) {
+                            index += 1;
+                            match token {
+                                TokenTree::Punct(punct) if punct.as_char() == '<' => {
+                                    angle_depth += 1
+                                }
+                                TokenTree::Punct(punct) if punct.as_char() == '>' => {
+                                    angle_depth -= 1;
+                                    if angle_depth == 0 {
+                                        break;
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                    } else {
+                        break;
+                    }
                 }
             }
+            _ => return index.min(tokens.len()),
+        }
+        if is_joint(tokens, index, '|', '|') {
+            index += 2;
+        } else {
+            return index.min(tokens.len());
+        }
+    }
+}
+
+/// Formats the variants of a set or the fields of a struct or variant, one per line
+fn format_list(group: &proc_macro2::Group, depth: usize) -> String {
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+    let mut entries = Vec::new();
+    let mut entry_start = 0;
+    let mut angle_depth = 0usize;
+    for (index, token) in tokens.iter().enumerate() {
+        let TokenTree::Punct(punct) = token else {
+            continue;
+        };
+        match punct.as_char() {
+            '<' => angle_depth += 1,
+            '>' if !(index > 0 && is_joint(&tokens, index - 1, '-', '>')) => {
+                angle_depth = angle_depth.saturating_sub(1)
+            }
+            ',' if angle_depth == 0 => {
+                entries.push(&tokens[entry_start..index]);
+                entry_start = index + 1;
+            }
             _ => {}
         }
     }
+    entries.push(&tokens[entry_start..]);
+    entries.retain(|e| !e.is_empty());
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+    let mut formatted = String::from("{\n");
+    for entry in entries {
+        let (attributes, entry) = split_attributes(entry);
+        for attribute in attributes {
+            push_line(
+                &mut formatted,
+                depth + 1,
+                &format_attribute(attribute, depth + 1),
+            );
+        }
+        if !entry.is_empty() {
+            push_line(
+                &mut formatted,
+                depth + 1,
+                &format!("{},", format_tokens(entry, depth + 1, true)),
+            );
+        }
+    }
+    formatted.push_str(&INDENT.repeat(depth));
+    formatted.push('}');
+    formatted
+}
 
-    if depth == 0 {
-        let line_number = content[..brace_start_pos].chars().filter(|&c| c == '\n').count() + 1;
-        error_set_body.push_str(&format!("\t// From `{}:{line_number}`\n", current_file.display()));
-        error_set_body.push_str(&content[brace_start_pos + 1..end - 1].trim_start_matches('\n'));
-        error_set_body.push_str("\n");
-        end
-    } else {
-        panic!("Unmatched braces in error_set_part! macro in {}", current_file.display());
+/// Splits off the outer attributes at the start of [tokens], e.g. `#[display("..")]`
+fn split_attributes(mut tokens: &[TokenTree]) -> (Vec<&[TokenTree]>, &[TokenTree]) {
+    let mut attributes = Vec::new();
+    while let [TokenTree::Punct(punct), TokenTree::Group(group), ..] = tokens {
+        if punct.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+            break;
+        }
+        attributes.push(&tokens[..2]);
+        tokens = &tokens[2..];
+    }
+    (attributes, tokens)
+}
+
+/// Formats an attribute, with single line docs as `///` comments
+fn format_attribute(attribute: &[TokenTree], depth: usize) -> String {
+    if let [_, TokenTree::Group(group)] = attribute {
+        if let [
+            TokenTree::Ident(ident),
+            TokenTree::Punct(punct),
+            TokenTree::Literal(literal),
+        ] = &group.stream().into_iter().collect::<Vec<_>>()[..]
+        {
+            if ident == "doc" && punct.as_char() == '=' {
+                if let syn::Lit::Str(doc) = syn::Lit::new(literal.clone()) {
+                    if !doc.value().contains('\n') {
+                        return format!("///{}", doc.value());
+                    }
+                }
+            }
+        }
+    }
+    format_tokens(attribute, depth, false)
+}
+
+/// Formats [tokens] on one line, except for brace groups when [lists] is set, see [format_list]
+fn format_tokens(tokens: &[TokenTree], depth: usize, lists: bool) -> String {
+    let mut formatted = String::new();
+    let mut previous: Option<Atom> = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        let atom = match &tokens[index] {
+            TokenTree::Ident(ident) => Atom::Word(ident.to_string()),
+            TokenTree::Literal(literal) => Atom::Word(literal.to_string()),
+            TokenTree::Punct(punct) => match tokens.get(index + 1) {
+                // e.g. `'a`
+                Some(TokenTree::Ident(ident)) if punct.as_char() == '\'' => {
+                    index += 1;
+                    Atom::Word(format!("'{ident}"))
+                }
+                Some(TokenTree::Punct(next))
+                    if punct.spacing() == Spacing::Joint
+                        && matches!(
+                            (punct.as_char(), next.as_char()),
+                            (':', ':' | '=') | ('|', '|') | ('-' | '=', '>') | ('.', '.')
+                        ) =>
+                {
+                    index += 1;
+                    Atom::Punct(format!("{}{}", punct.as_char(), next.as_char()))
+                }
+                _ => Atom::Punct(punct.as_char().to_string()),
+            },
+            TokenTree::Group(group) => Atom::Group(group.delimiter()),
+        };
+        if previous.as_ref().is_some_and(|e| needs_space(e, &atom)) {
+            formatted.push(' ');
+        }
+        if let TokenTree::Group(group) = &tokens[index] {
+            let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+            match group.delimiter() {
+                Delimiter::Parenthesis => {
+                    formatted.push_str(&format!("({})", format_tokens(&inner, depth, false)))
+                }
+                Delimiter::Bracket => {
+                    formatted.push_str(&format!("[{}]", format_tokens(&inner, depth, false)))
+                }
+                Delimiter::Brace if lists => formatted.push_str(&format_list(group, depth)),
+                Delimiter::Brace if inner.is_empty() => formatted.push_str("{}"),
+                Delimiter::Brace => {
+                    formatted.push_str(&format!("{{ {} }}", format_tokens(&inner, depth, false)))
+                }
+                Delimiter::None => formatted.push_str(&format_tokens(&inner, depth, lists)),
+            }
+        } else if let Atom::Word(text) | Atom::Punct(text) = &atom {
+            formatted.push_str(text);
+        }
+        previous = Some(atom);
+        index += 1;
+    }
+    formatted
+}
+
+/// A token as far as spacing is concerned, with multi-character punctuation such as `::` joined
+enum Atom {
+    Word(String),
+    Punct(String),
+    Group(Delimiter),
+}
+
+/// Whether a space goes between [previous] and [next], e.g. `a: B<C> || D`
+fn needs_space(previous: &Atom, next: &Atom) -> bool {
+    if let Atom::Punct(next) = next {
+        if matches!(
+            next.as_str(),
+            "," | ";" | ":" | "::" | "." | ".." | "<" | ">"
+        ) {
+            return false;
+        }
+    }
+    match (previous, next) {
+        (Atom::Punct(previous), _) => !matches!(
+            previous.as_str(),
+            "::" | "<" | "&" | "?" | "!" | "#" | "*" | "-" | "." | ".."
+        ),
+        (Atom::Word(_), Atom::Group(Delimiter::Parenthesis | Delimiter::Bracket)) => false,
+        _ => true,
+    }
+}
+
+/// Whether [tokens] has the joint punctuation [first][second] at [index], e.g. `::`
+fn is_joint(tokens: &[TokenTree], index: usize, first: char, second: char) -> bool {
+    matches!(
+        (tokens.get(index), tokens.get(index + 1)),
+        (Some(TokenTree::Punct(a)), Some(TokenTree::Punct(b)))
+            if a.as_char() == first && a.spacing() == Spacing::Joint && b.as_char() == second
+    )
+}
+
+fn push_line(formatted: &mut String, depth: usize, line: &str) {
+    formatted.push_str(&INDENT.repeat(depth));
+    formatted.push_str(line);
+    formatted.push('\n');
+}
+
+struct PartMacroVisitor<'ast> {
+    parts: Vec<&'ast syn::Macro>,
+}
+
+impl<'ast> Visit<'ast> for PartMacroVisitor<'ast> {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        // e.g. `error_set_part!` or `error_set::error_set_part!`
+        if mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|e| e.ident == PART_MACRO_NAME)
+        {
+            self.parts.push(mac);
+        }
+        syn::visit::visit_macro(self, mac);
     }
 }
 
@@ -118,14 +639,195 @@ mod tests {
                 MyError1,
                 MyError2,
             }
-            
+
             fn some_function() {}
-            
+
             error_set_part! { AnotherError }
         "#;
-        let mut parts = String::new();
-        extract_error_set_parts(code, &mut parts, &PathBuf::from("test"));
+        let mut parts = Vec::new();
+        extract_error_set_parts(code, &mut parts, &PathBuf::from("test"), None).unwrap();
+        let parts = render(&parts);
         assert!(parts.contains("MyError1"));
         assert!(parts.contains("AnotherError"));
     }
+
+    #[test]
+    fn test_extract_error_set_parts_ignores_braces_in_strings_and_comments() {
+        let code = r##"
+            // error_set_part! { CommentedOut := { A } }
+            const NOT_A_PART: &str = "error_set_part! { InString := { A } }";
+
+            error_set::error_set_part! {
+                MyError := {
+                    #[display("unbalanced {{ and {field}")]
+                    A { field: String },
+                    /// doc with a } brace
+                    #[display(r#"raw "}" string"#)]
+                    B,
+                }
+            }
+        "##;
+        let mut parts = Vec::new();
+        extract_error_set_parts(code, &mut parts, &PathBuf::from("test"), None).unwrap();
+        let parts = render(&parts);
+        assert!(!parts.contains("CommentedOut"));
+        assert!(!parts.contains("InString"));
+        assert!(parts.contains("// From `test:5`"));
+        assert!(parts.contains(r##"#[display(r#"raw "}" string"#)]"##));
+        assert!(parts.ends_with("        B,\n    }\n}\n"));
+    }
+
+    #[test]
+    fn test_extract_error_set_parts_skips_module_attribute() {
+        let code = r#"
+            error_set_part! {
+                #![module(crate::errors)]
+                MyError := { A }
+            }
+        "#;
+        let mut parts = Vec::new();
+        extract_error_set_parts(code, &mut parts, &PathBuf::from("test"), None).unwrap();
+        let parts = render(&parts);
+        assert!(!parts.contains("module"));
+        assert!(parts.contains("    MyError := {\n        A,\n    }\n"));
+    }
+
+    #[test]
+    fn test_extract_error_set_parts_records_crate() {
+        let code = "error_set_part! { MyError := { A } }";
+        let mut parts = Vec::new();
+        extract_error_set_parts(
+            code,
+            &mut parts,
+            &PathBuf::from("../auth/src/lib.rs"),
+            Some("auth"),
+        )
+        .unwrap();
+        let parts = render(&parts);
+        assert!(parts.contains("// From `auth` at `../auth/src/lib.rs:1`"));
+    }
+
+    #[test]
+    fn test_extract_error_set_parts_keeps_multi_line_literals() {
+        let code = "mod a {\n\terror_set_part! {\n\t\tMyError := {\n\t\t\t#[display(\"multi\n    line\")]   \n\t\t\tA,\n\t\t}\n\t}\n}\n";
+        let mut parts = Vec::new();
+        extract_error_set_parts(code, &mut parts, &PathBuf::from("test"), None).unwrap();
+        assert_eq!(
+            parts[0].body,
+            "    MyError := {\n        #[display(\"multi\n    line\")]\n        A,\n    }\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_error_set_parts_formats_body() {
+        let code = r#"
+            error_set_part! {
+                /// Docs
+                #[display(humanize)] C<'a,T:Debug> := {Field1,Field2(&'a T){ a:Vec<(u8,String)>,b :u8 },Field3{}} || crate::B<T>
+                struct D{x:u8}
+                A := {Field ,}
+            }
+        "#;
+        let mut parts = Vec::new();
+        extract_error_set_parts(code, &mut parts, &PathBuf::from("test"), None).unwrap();
+        assert_eq!(
+            parts[0].body,
+            r#"    /// Docs
+    #[display(humanize)]
+    C<'a, T: Debug> := {
+        Field1,
+        Field2(&'a T) {
+            a: Vec<(u8, String)>,
+            b: u8,
+        },
+        Field3 {},
+    } || crate::B<T>
+    struct D {
+        x: u8,
+    }
+    A := {
+        Field,
+    }
+"#
+        );
+    }
+
+    #[test]
+    fn test_output_module_path() {
+        let combine_parts = CombineParts::new().output_module("errors");
+        assert!(
+            matches!(combine_parts.output, Output::Path(path) if path == Path::new("src/errors.rs"))
+        );
+        let combine_parts = CombineParts::new().output_module("a::errors");
+        assert!(
+            matches!(combine_parts.output, Output::Path(path) if path == Path::new("src/a/errors.rs"))
+        );
+    }
+
+    #[test]
+    fn test_run_only_rewrites_changed_output() {
+        let dir =
+            std::env::temp_dir().join(format!("error_set_combine_parts_{}", std::process::id()));
+        let src = dir.join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("part.rs"), "error_set_part! { A := { B } }").unwrap();
+        let output = dir.join("error_set.rs");
+        let run = || {
+            CombineParts::new()
+                .scan(&src)
+                .output(&output)
+                .rerun_if_changed(false)
+                .run()
+                .unwrap();
+            fs::metadata(&output).unwrap().modified().unwrap()
+        };
+        let first_modified = run();
+        assert!(
+            fs::read_to_string(&output)
+                .unwrap()
+                .contains("A := {\n        B,\n    }")
+        );
+        assert_eq!(run(), first_modified);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_sorts_parts_by_path() {
+        let dir = std::env::temp_dir().join(format!(
+            "error_set_combine_parts_sorted_{}",
+            std::process::id()
+        ));
+        let src = dir.join("src");
+        fs::create_dir_all(src.join("b")).unwrap();
+        fs::write(src.join("b/mod.rs"), "error_set_part! { B := { B } }").unwrap();
+        fs::write(src.join("c.rs"), "error_set_part! { C := { C } }").unwrap();
+        fs::write(
+            src.join("a.rs"),
+            "error_set_part! { A1 := { A } }\nerror_set_part! { A2 := { A } }",
+        )
+        .unwrap();
+        let output = dir.join("error_set.rs");
+        CombineParts::new()
+            .scan(&src)
+            .scan(&src)
+            .output(&output)
+            .rerun_if_changed(false)
+            .run()
+            .unwrap();
+        let output = fs::read_to_string(&output).unwrap();
+        let positions = ["A1 :=", "A2 :=", "B :=", "C :="].map(|e| output.find(e).unwrap());
+        assert!(positions.is_sorted());
+        assert_eq!(output.matches("A1 :=").count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_error_set_parts_reports_location() {
+        let code = "fn main() {}\n\nerror_set_part!(MyError := { A });\n";
+        let mut parts: Vec<Part> = Vec::new();
+        let error = extract_error_set_parts(code, &mut parts, &PathBuf::from("src/test.rs"), None)
+            .unwrap_err();
+        assert_eq!(error.line(), Some(3));
+        assert!(error.to_string().starts_with("src/test.rs:3:1: "));
+    }
 }
@@ -0,0 +1,51 @@
+use std::error::Error;
+use std::process::{ExitCode, Termination};
+
+use crate::{ReportStyle, ToExitCode, write_report};
+
+/// The result of `main` for a CLI. On error, the error and its `source()` chain are printed to stderr, and the process
+/// exits with the error's [`ToExitCode::exit_code`].
+/// ```rust
+/// error_set::error_set! {
+///     CliError := {
+///         #[exit_code(2)]
+///         #[display("missing argument `{name}`")]
+///         MissingArgument { name: String },
+///     }
+/// }
+///
+/// fn run() -> Result<(), CliError> {
+///     Ok(())
+/// }
+///
+/// fn main() -> error_set::Exit<CliError> {
+///     run().into()
+/// }
+/// ```
+pub struct Exit<E>(pub Result<(), E>);
+
+impl<E> From<Result<(), E>> for Exit<E> {
+    fn from(result: Result<(), E>) -> Self {
+        Exit(result)
+    }
+}
+
+impl<E> From<E> for Exit<E> {
+    fn from(error: E) -> Self {
+        Exit(Err(error))
+    }
+}
+
+impl<E: Error + ToExitCode> Termination for Exit<E> {
+    fn report(self) -> ExitCode {
+        match self.0 {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                let mut message = String::new();
+                let _ = write_report(&mut message, &error, ReportStyle::MultiLine);
+                eprintln!("Error: {message}");
+                ExitCode::from(error.exit_code())
+            }
+        }
+    }
+}
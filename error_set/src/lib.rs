@@ -1,14 +1,26 @@
-#![cfg_attr(not(any(test, feature = "combine_parts")), no_std)]
+#![cfg_attr(not(any(test, feature = "std", feature = "combine_parts")), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
 #[cfg(feature = "combine_parts")]
 mod combine_parts;
 #[cfg(feature = "combine_parts")]
-pub use combine_parts::combine_error_set_parts;
+pub use combine_parts::{CombineParts, CombinePartsError, combine_error_set_parts};
+#[cfg(feature = "std")]
+mod exit;
+#[cfg(feature = "std")]
+pub use exit::Exit;
+mod report;
+pub use report::{Report, ReportStyle, write_report};
 
 pub use error_set_impl::*;
 
+// Dev Note: Used by the generated `level`, `trace` and `From` conversions, so users do not need to depend on
+// `tracing` directly.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing;
+
 pub trait CoerceResult<T, E1> {
     fn coerce<E2: From<E1>>(self) -> Result<T, E2>;
 }
@@ -19,3 +31,28 @@ impl<T, E1> CoerceResult<T, E1> for Result<T, E1> {
         self.map_err(Into::<E2>::into)
     }
 }
+
+/// If an error is transient, so the operation that caused it may succeed if retried. Implemented by every error set,
+/// and used by variants marked `#[retryable(source)]` to ask their source.
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+/// Interrupted, timed out and would block operations may succeed if retried.
+#[cfg(feature = "std")]
+impl Retryable for std::io::Error {
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            std::io::ErrorKind::Interrupted
+                | std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::WouldBlock
+        )
+    }
+}
+
+/// The process exit code of an error. Implemented by error sets with any `#[exit_code(..)]`, and used by `Exit` when the
+/// error is returned from `main`.
+pub trait ToExitCode {
+    fn exit_code(&self) -> u8;
+}
@@ -0,0 +1,184 @@
+use core::error::Error;
+use core::fmt::{self, Write as _};
+
+/// How a [`Report`] lays out an error and its sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportStyle {
+    /// e.g. `failed to load config: failed to read config: permission denied`
+    #[default]
+    SingleLine,
+    /// e.g.
+    /// ```text
+    /// failed to load config
+    ///
+    /// Caused by:
+    ///     0: failed to read config
+    ///     1: permission denied
+    /// ```
+    MultiLine,
+}
+
+/// Formats an error together with its `source()` chain. `Display` uses the configured [`ReportStyle`], while `Debug`
+/// always uses [`ReportStyle::MultiLine`], so returning `Result<(), Report<E>>` from `main` prints the whole chain.
+/// ```rust
+/// error_set::error_set! {
+///     ConfigError := {
+///         #[display("failed to read config")]
+///         Read(std::io::Error),
+///     }
+/// }
+///
+/// let error = ConfigError::Read(std::io::Error::other("permission denied"));
+/// let report = error_set::Report::new(error);
+/// assert_eq!(report.to_string(), "failed to read config: permission denied");
+/// ```
+/// With the `std` feature, a backtrace is captured when the report is created and included in the multi-line
+/// output if one was captured, i.e. if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set.
+pub struct Report<E> {
+    error: E,
+    style: ReportStyle,
+    #[cfg(feature = "std")]
+    backtrace: std::backtrace::Backtrace,
+}
+
+impl<E> Report<E> {
+    pub fn new(error: E) -> Self {
+        Report {
+            error,
+            style: ReportStyle::default(),
+            #[cfg(feature = "std")]
+            backtrace: std::backtrace::Backtrace::capture(),
+        }
+    }
+
+    /// Sets the style used by `Display`.
+    pub fn style(mut self, style: ReportStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Shorthand for `style(ReportStyle::MultiLine)`.
+    pub fn pretty(self) -> Self {
+        self.style(ReportStyle::MultiLine)
+    }
+
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    pub fn into_error(self) -> E {
+        self.error
+    }
+
+    #[cfg(feature = "std")]
+    pub fn backtrace(&self) -> &std::backtrace::Backtrace {
+        &self.backtrace
+    }
+}
+
+impl<E: Error> Report<E> {
+    fn write(&self, f: &mut fmt::Formatter<'_>, style: ReportStyle) -> fmt::Result {
+        write_report(f, &self.error, style)?;
+        #[cfg(feature = "std")]
+        if style == ReportStyle::MultiLine
+            && self.backtrace.status() == std::backtrace::BacktraceStatus::Captured
+        {
+            write!(f, "\n\nStack backtrace:\n{}", self.backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Error> From<E> for Report<E> {
+    fn from(error: E) -> Self {
+        Report::new(error)
+    }
+}
+
+impl<E: Error> fmt::Display for Report<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, self.style)
+    }
+}
+
+impl<E: Error> fmt::Debug for Report<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, ReportStyle::MultiLine)
+    }
+}
+
+/// Writes an error and its `source()` chain in the given style, without allocating. Unlike [`Report`], this never
+/// includes a backtrace.
+/// ```rust
+/// let error = "x".parse::<u8>().unwrap_err();
+/// let mut out = String::new();
+/// error_set::write_report(&mut out, &error, error_set::ReportStyle::SingleLine).unwrap();
+/// assert_eq!(out, "invalid digit found in string");
+/// ```
+pub fn write_report<W: fmt::Write + ?Sized>(
+    writer: &mut W,
+    error: &dyn Error,
+    style: ReportStyle,
+) -> fmt::Result {
+    write!(writer, "{}", error)?;
+    let Some(first) = error.source() else {
+        return Ok(());
+    };
+    match style {
+        ReportStyle::SingleLine => {
+            let mut source = Some(first);
+            while let Some(error) = source {
+                write!(writer, ": {}", error)?;
+                source = error.source();
+            }
+        }
+        ReportStyle::MultiLine => {
+            writer.write_str("\n\nCaused by:")?;
+            if first.source().is_none() {
+                writer.write_str("\n    ")?;
+                return write!(
+                    Indented {
+                        writer,
+                        indent: "\n    "
+                    },
+                    "{}",
+                    first
+                );
+            }
+            let mut source = Some(first);
+            let mut index = 0;
+            while let Some(error) = source {
+                write!(writer, "\n    {}: ", index)?;
+                write!(
+                    Indented {
+                        writer: &mut *writer,
+                        indent: "\n       ",
+                    },
+                    "{}",
+                    error
+                )?;
+                source = error.source();
+                index += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Indents every line after the first, so multi-line messages of sources stay under their entry.
+struct Indented<'a, W: ?Sized> {
+    writer: &'a mut W,
+    indent: &'static str,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Indented<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.writer.write_str(self.indent)?;
+            }
+            self.writer.write_str(line)?;
+        }
+        Ok(())
+    }
+}
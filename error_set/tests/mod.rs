@@ -683,6 +683,34 @@ pub mod display_ref_error {
     }
 }
 
+#[cfg(test)]
+pub mod display_captures {
+    use error_set::error_set;
+
+    const MAX: u32 = 5;
+    static UNIT: &str = "MB";
+
+    error_set! {
+        UploadError := {
+            #[display("value exceeds {MAX}")]
+            TooMany,
+            #[display("{size}{UNIT} exceeds {MAX}{UNIT}")]
+            TooLarge {
+                size: u32,
+            },
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(UploadError::TooMany.to_string(), "value exceeds 5");
+        assert_eq!(
+            UploadError::TooLarge { size: 7 }.to_string(),
+            "7MB exceeds 5MB"
+        );
+    }
+}
+
 #[cfg(test)]
 pub mod fields_with_unique_types {
     use error_set::error_set;
@@ -1090,6 +1118,1026 @@ pub mod source_only_returns_one_level_down {
     }
 }
 
+#[cfg(test)]
+pub mod strict {
+    use error_set::error_set;
+
+    error_set! {
+        #[strict]
+        TransferError := DownloadError || UploadError
+        DownloadError := {
+            Timeout {
+                secs: u64
+            },
+            IoError(std::io::Error),
+        }
+        UploadError := {
+            Timeout {
+                secs: u64
+            },
+            Rejected,
+        }
+    }
+
+    #[test]
+    fn test() {
+        let download_error = DownloadError::Timeout { secs: 1 };
+        let transfer_error: TransferError = download_error.into();
+        assert!(matches!(transfer_error, TransferError::Timeout { secs: 1 }));
+        let upload_error = UploadError::Timeout { secs: 2 };
+        let transfer_error: TransferError = upload_error.into();
+        assert!(matches!(transfer_error, TransferError::Timeout { secs: 2 }));
+    }
+}
+
+#[cfg(test)]
+pub mod explain {
+    use error_set::error_set;
+
+    error_set! {
+        #[explain]
+        #[skip(From(std::fmt::Error))]
+        TransferError := {
+            IoError(std::io::Error),
+            OtherIoError(std::io::Error),
+            FmtError(std::fmt::Error),
+        } || DownloadError || UploadError
+        DownloadError := {
+            Timeout {
+                secs: u64
+            },
+        }
+        UploadError := {
+            Timeout {
+                ms: u64
+            },
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            TransferError::EXPLAIN,
+            "`From` implementations for `TransferError`
+generated:
+  From<DownloadError>
+not generated:
+  From<UploadError> - shape mismatch, `UploadError::Timeout { ms: u64 }` does not match `TransferError::Timeout { secs: u64 }`
+  From<std::io::Error> (into `IoError`) - the source type of multiple variants - `IoError`, `OtherIoError`
+  From<std::fmt::Error> (into `FmtError`) - skipped with `#[skip(From(..))]`
+"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod display_doc {
+    use error_set::error_set;
+
+    error_set! {
+        #[display(doc)]
+        DownloadError := {
+            /// Timed out after {secs}s
+            ///
+            /// Retried with backoff before giving up.
+            Timeout {
+                secs: u64
+            },
+            /// Download failed: {0}
+            IoError(std::io::Error),
+            #[display("Explicit display wins")]
+            /// Ignored doc
+            Explicit,
+            NoDoc,
+        }
+        MediaError := DownloadError || {
+            /// Not used, `MediaError` does not opt in
+            Parse,
+        }
+        DiskError := {
+            /// Disk is full
+            Full,
+        }
+        // Opts in for the variants of `DiskError` too
+        #[display(doc)]
+        StorageError := DiskError
+        /// The user `{name}` was not found
+        #[display(doc)]
+        struct UserNotFound {
+            name: String,
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            DownloadError::Timeout { secs: 3 }.to_string(),
+            "Timed out after 3s"
+        );
+        assert_eq!(
+            DownloadError::IoError(std::io::Error::other("closed")).to_string(),
+            "Download failed: closed"
+        );
+        assert_eq!(
+            DownloadError::Explicit.to_string(),
+            "Explicit display wins"
+        );
+        assert_eq!(DownloadError::NoDoc.to_string(), "DownloadError::NoDoc");
+        assert_eq!(
+            MediaError::Timeout { secs: 3 }.to_string(),
+            "Timed out after 3s"
+        );
+        assert_eq!(MediaError::Parse.to_string(), "MediaError::Parse");
+        assert_eq!(DiskError::Full.to_string(), "DiskError::Full");
+        assert_eq!(StorageError::Full.to_string(), "Disk is full");
+        assert_eq!(
+            UserNotFound {
+                name: "ferris".to_string()
+            }
+            .to_string(),
+            "The user `ferris` was not found"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod display_policy {
+    use error_set::error_set;
+
+    error_set! {
+        #[display(humanize)]
+        BookError := {
+            MissingBookDescription,
+            HTTPError,
+            Utf8Error,
+            #[display("explicit")]
+            Explicit,
+            IoError(std::io::Error),
+        }
+        #[display(template = "[{set}] {message}")]
+        LibraryError := BookError || {
+            #[display("closed on {day}")]
+            Closed { day: String },
+            Lost,
+        }
+        // Does not declare a policy, so the variants of `BookError` keep theirs
+        ShelfError := BookError || {
+            Empty,
+        }
+        #[display(doc, humanize, template = "{{{message}}}")]
+        DocError := {
+            /// Documented
+            Documented,
+            NotDocumented,
+        }
+        // Replaces the template of `DocError`, but keeps its `humanize`
+        #[display(template = "<{message}>")]
+        ArchiveError := DocError
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            BookError::MissingBookDescription.to_string(),
+            "missing book description"
+        );
+        assert_eq!(BookError::HTTPError.to_string(), "http error");
+        assert_eq!(BookError::Utf8Error.to_string(), "utf8 error");
+        assert_eq!(BookError::Explicit.to_string(), "explicit");
+        assert_eq!(
+            BookError::IoError(std::io::Error::other("disk")).to_string(),
+            "disk"
+        );
+
+        assert_eq!(
+            LibraryError::MissingBookDescription.to_string(),
+            "[LibraryError] missing book description"
+        );
+        assert_eq!(
+            LibraryError::Closed {
+                day: "Sunday".to_string()
+            }
+            .to_string(),
+            "[LibraryError] closed on Sunday"
+        );
+        assert_eq!(LibraryError::Lost.to_string(), "[LibraryError] LibraryError::Lost");
+        assert_eq!(
+            LibraryError::IoError(std::io::Error::other("disk")).to_string(),
+            "[LibraryError] disk"
+        );
+
+        assert_eq!(
+            ShelfError::MissingBookDescription.to_string(),
+            "missing book description"
+        );
+        assert_eq!(ShelfError::Empty.to_string(), "ShelfError::Empty");
+
+        assert_eq!(DocError::Documented.to_string(), "{Documented}");
+        assert_eq!(DocError::NotDocumented.to_string(), "{not documented}");
+        assert_eq!(ArchiveError::Documented.to_string(), "<Documented>");
+        assert_eq!(
+            ArchiveError::NotDocumented.to_string(),
+            "<not documented>"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod display_chain {
+    use error_set::error_set;
+
+    error_set! {
+        ConfigError := {
+            #[display(chain, "failed to read config")]
+            IoError(std::io::Error),
+            #[display(chain, "failed to parse {path}")]
+            ParseError(std::num::ParseIntError) {
+                path: String,
+            },
+            #[display(chain)]
+            FmtError(std::fmt::Error),
+            Delegated(std::str::Utf8Error),
+        }
+        #[display(chain, humanize)]
+        LoadError := {
+            #[display("failed to load")]
+            IoError(std::io::Error),
+            NotFound,
+        }
+        #[display(template = "[{set}] {message}")]
+        AppError := ConfigError
+        #[display(humanize)]
+        ReadError := {
+            #[display("failed to read config")]
+            ReadConfig(std::io::Error),
+            MissingBook,
+        }
+        // Adds `chain` to the `humanize` of `ReadError`
+        #[display(chain)]
+        StartupError := ReadError
+        #[display(chain, "failed to open {path}")]
+        struct OpenError {
+            path: String,
+            source: std::io::Error,
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            ConfigError::IoError(std::io::Error::other("permission denied")).to_string(),
+            "failed to read config: permission denied"
+        );
+        let parse_error = "x".parse::<u32>().unwrap_err();
+        assert_eq!(
+            ConfigError::ParseError {
+                source: parse_error.clone(),
+                path: "config.toml".to_string()
+            }
+            .to_string(),
+            format!("failed to parse config.toml: {parse_error}")
+        );
+        assert_eq!(
+            ConfigError::FmtError(std::fmt::Error).to_string(),
+            format!("ConfigError::FmtError: {}", std::fmt::Error)
+        );
+        let utf8_error = String::from_utf8(vec![0xff]).unwrap_err().utf8_error();
+        assert_eq!(
+            ConfigError::Delegated(utf8_error).to_string(),
+            utf8_error.to_string()
+        );
+        assert_eq!(
+            LoadError::IoError(std::io::Error::other("disk")).to_string(),
+            "failed to load: disk"
+        );
+        assert_eq!(LoadError::NotFound.to_string(), "not found");
+        assert_eq!(
+            AppError::IoError(std::io::Error::other("permission denied")).to_string(),
+            "[AppError] failed to read config: permission denied"
+        );
+        assert_eq!(
+            ReadError::ReadConfig(std::io::Error::other("disk")).to_string(),
+            "failed to read config"
+        );
+        assert_eq!(
+            StartupError::ReadConfig(std::io::Error::other("disk")).to_string(),
+            "failed to read config: disk"
+        );
+        assert_eq!(StartupError::MissingBook.to_string(), "missing book");
+        assert_eq!(
+            OpenError {
+                path: "a.txt".to_string(),
+                source: std::io::Error::other("missing")
+            }
+            .to_string(),
+            "failed to open a.txt: missing"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod display_with {
+    use core::fmt;
+
+    use error_set::error_set;
+
+    error_set! {
+        UploadError := {
+            #[display(with = fmt_too_large)]
+            TooLarge {
+                size: u64,
+                limit: u64,
+            },
+            #[display(with = self::fmt_missing)]
+            Missing,
+            #[display(chain, with = fmt_read)]
+            Read(std::io::Error) {
+                retries: u32,
+            },
+            #[display(with = fmt_parse)]
+            Parse(std::num::ParseIntError),
+        }
+        SyncError := UploadError || { Offline }
+        #[display(with = fmt_quota)]
+        struct QuotaError {
+            files: usize,
+        }
+    }
+
+    fn fmt_too_large(size: &u64, limit: &u64, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} KiB is over the {} KiB limit",
+            size / 1024,
+            limit / 1024
+        )
+    }
+
+    fn fmt_missing(f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("nothing to upload")
+    }
+
+    fn fmt_read(_source: &std::io::Error, retries: &u32, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "read failed after {} {}",
+            retries,
+            if *retries == 1 { "retry" } else { "retries" }
+        )
+    }
+
+    fn fmt_parse(source: &std::num::ParseIntError, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad size ({})", source)
+    }
+
+    fn fmt_quota(files: &usize, f: &mut fmt::Formatter) -> fmt::Result {
+        match files {
+            1 => f.write_str("quota exceeded by 1 file"),
+            n => write!(f, "quota exceeded by {} files", n),
+        }
+    }
+
+    #[test]
+    fn test() {
+        let too_large = UploadError::TooLarge {
+            size: 4096,
+            limit: 2048,
+        };
+        assert_eq!(too_large.to_string(), "4 KiB is over the 2 KiB limit");
+        let too_large: SyncError = too_large.into();
+        assert_eq!(too_large.to_string(), "4 KiB is over the 2 KiB limit");
+        assert_eq!(SyncError::Missing.to_string(), "nothing to upload");
+        assert_eq!(SyncError::Offline.to_string(), "SyncError::Offline");
+        assert_eq!(
+            UploadError::Read {
+                source: std::io::Error::other("disk"),
+                retries: 1
+            }
+            .to_string(),
+            "read failed after 1 retry: disk"
+        );
+        let parse: SyncError = UploadError::Parse("x".parse::<u64>().unwrap_err()).into();
+        assert_eq!(
+            parse.to_string(),
+            "bad size (invalid digit found in string)"
+        );
+        assert_eq!(
+            QuotaError { files: 1 }.to_string(),
+            "quota exceeded by 1 file"
+        );
+        assert_eq!(
+            QuotaError { files: 3 }.to_string(),
+            "quota exceeded by 3 files"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod redact {
+    use core::fmt;
+
+    use error_set::error_set;
+
+    error_set! {
+        AuthError := {
+            #[display("invalid token {token} for {email} after {attempts} attempts")]
+            InvalidToken {
+                #[redact]
+                token: String,
+                #[redact]
+                email: String,
+                attempts: u32,
+            },
+            Io(std::io::Error) {
+                #[redact]
+                path: String,
+            },
+            Expired,
+        }
+        LoginError := AuthError || { Offline }
+        GenericError<T: fmt::Debug> := {
+            #[display("bad value {value}")]
+            Bad {
+                #[redact]
+                value: T,
+            },
+        }
+        #[display("session {session} expired for {user}")]
+        struct SessionError {
+            #[redact]
+            session: String,
+            user: u32,
+        }
+    }
+
+    #[test]
+    fn test() {
+        let invalid = AuthError::InvalidToken {
+            token: "secret".to_string(),
+            email: "a@b.c".to_string(),
+            attempts: 2,
+        };
+        assert_eq!(
+            invalid.to_string(),
+            "invalid token <redacted> for <redacted> after 2 attempts"
+        );
+        assert_eq!(
+            format!("{:?}", invalid),
+            "InvalidToken { token: <redacted>, email: <redacted>, attempts: 2 }"
+        );
+        let invalid: LoginError = invalid.into();
+        assert_eq!(
+            invalid.to_string(),
+            "invalid token <redacted> for <redacted> after 2 attempts"
+        );
+        assert_eq!(
+            format!("{:?}", invalid),
+            "InvalidToken { token: <redacted>, email: <redacted>, attempts: 2 }"
+        );
+        let io = LoginError::Io {
+            source: std::io::Error::other("disk"),
+            path: "/secret".to_string(),
+        };
+        assert!(!format!("{:?}", io).contains("/secret"));
+        assert_eq!(format!("{:?}", LoginError::Expired), "Expired");
+        assert_eq!(format!("{:?}", LoginError::Offline), "Offline");
+
+        let bad = GenericError::Bad { value: 3 };
+        assert_eq!(bad.to_string(), "bad value <redacted>");
+        assert_eq!(format!("{:?}", bad), "Bad { value: <redacted> }");
+
+        let session = SessionError {
+            session: "abc".to_string(),
+            user: 1,
+        };
+        assert_eq!(session.session, "abc");
+        assert_eq!(session.to_string(), "session <redacted> expired for 1");
+        assert_eq!(
+            format!("{:?}", session),
+            "SessionError { session: <redacted>, user: 1 }"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod debug_format {
+    use error_set::error_set;
+
+    error_set! {
+        FetchError := {
+            #[display("timed out after {secs}s")]
+            #[debug("timeout({secs}s)")]
+            Timeout {
+                secs: u64,
+            },
+            #[debug("io({})")]
+            Io(std::io::Error),
+            #[display("user {user} is not authorized")]
+            #[debug("auth(user={user}, token={token})")]
+            Auth {
+                user: u32,
+                #[redact]
+                token: String,
+            },
+            Closed,
+        }
+        #[debug(display)]
+        #[display(humanize)]
+        AppError := FetchError || {
+            #[display("config missing at {path}")]
+            Config {
+                path: String,
+            },
+            Parse(std::num::ParseIntError),
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            format!("{:?}", FetchError::Timeout { secs: 3 }),
+            "timeout(3s)"
+        );
+        assert_eq!(
+            format!("{:?}", FetchError::Io(std::io::Error::other("reset"))),
+            "io(reset)"
+        );
+        let auth = FetchError::Auth {
+            user: 7,
+            token: "secret".to_string(),
+        };
+        assert_eq!(format!("{:?}", auth), "auth(user=7, token=<redacted>)");
+        assert_eq!(format!("{:?}", FetchError::Closed), "Closed");
+
+        let auth: AppError = auth.into();
+        assert_eq!(format!("{:?}", auth), "auth(user=7, token=<redacted>)");
+        let timeout: AppError = FetchError::Timeout { secs: 3 }.into();
+        assert_eq!(format!("{:?}", timeout), "timeout(3s)");
+        assert_eq!(format!("{:?}", AppError::Closed), "Closed: closed");
+        assert_eq!(
+            format!(
+                "{:?}",
+                AppError::Config {
+                    path: "a.toml".to_string()
+                }
+            ),
+            "Config: config missing at a.toml"
+        );
+        assert_eq!(
+            format!("{:?}", AppError::Parse("x".parse::<u8>().unwrap_err())),
+            "Parse: invalid digit found in string"
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod retryable {
+    use error_set::{Retryable, error_set};
+
+    #[derive(Debug)]
+    pub struct HttpError {
+        status: u16,
+    }
+
+    impl core::fmt::Display for HttpError {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "status {}", self.status)
+        }
+    }
+
+    impl std::error::Error for HttpError {}
+
+    impl Retryable for HttpError {
+        fn is_retryable(&self) -> bool {
+            self.status >= 500
+        }
+    }
+
+    error_set! {
+        FetchError := {
+            #[display("timed out after {secs}s")]
+            #[retryable]
+            Timeout {
+                secs: u64,
+            },
+            #[retryable]
+            RateLimited,
+            NotFound,
+            #[retryable(source)]
+            Http(HttpError),
+        }
+        SyncError := FetchError || { Conflict }
+        JobError := {
+            #[retryable(source)]
+            Sync(SyncError) {},
+            Cancelled,
+        }
+        CacheError := {
+            #[retryable]
+            Busy,
+        }
+        ParseError := {
+            InvalidHeader,
+        }
+        ImportError := {
+            #[retryable(source)]
+            Parse(ParseError),
+            #[retryable]
+            Locked,
+        }
+    }
+
+    mod validation {
+        error_set::error_set! {
+            ValidationError := {
+                Empty,
+            }
+        }
+    }
+
+    // Dev Note: A separate invocation, so the source set is only known through `Retryable`
+    error_set! {
+        UploadError := {
+            #[retryable(source)]
+            Validation(validation::ValidationError),
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert!(FetchError::Timeout { secs: 3 }.is_retryable());
+        assert!(FetchError::RateLimited.is_retryable());
+        assert!(!FetchError::NotFound.is_retryable());
+        assert!(FetchError::Http(HttpError { status: 503 }).is_retryable());
+        assert!(!FetchError::Http(HttpError { status: 404 }).is_retryable());
+
+        let sync: SyncError = FetchError::RateLimited.into();
+        assert!(sync.is_retryable());
+        let sync: SyncError = FetchError::Http(HttpError { status: 502 }).into();
+        assert!(sync.is_retryable());
+        assert!(!SyncError::NotFound.is_retryable());
+        assert!(!SyncError::Conflict.is_retryable());
+        let job: JobError = SyncError::Timeout { secs: 1 }.into();
+        assert!(job.is_retryable());
+        let job: JobError = SyncError::Conflict.into();
+        assert!(!job.is_retryable());
+        assert!(!JobError::Cancelled.is_retryable());
+
+        fn retryable(error: &dyn Retryable) -> bool {
+            error.is_retryable()
+        }
+        assert!(retryable(&CacheError::Busy));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_error_source() {
+        error_set! {
+            ReadError := {
+                #[retryable(source)]
+                Io(std::io::Error),
+            }
+        }
+
+        let error = ReadError::Io(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert!(error.is_retryable());
+        let error = ReadError::Io(std::io::Error::from(std::io::ErrorKind::Interrupted));
+        assert!(error.is_retryable());
+        let error = ReadError::Io(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn source_without_markers() {
+        assert!(!ImportError::Parse(ParseError::InvalidHeader).is_retryable());
+        assert!(ImportError::Locked.is_retryable());
+        assert!(!ParseError::InvalidHeader.is_retryable());
+        assert!(!UploadError::Validation(validation::ValidationError::Empty).is_retryable());
+    }
+}
+
+#[cfg(test)]
+pub mod exit_code {
+    use error_set::{ToExitCode, error_set};
+
+    error_set! {
+        #[exit_code(2)]
+        CliError := {
+            #[display("missing argument `{name}`")]
+            MissingArgument { name: String },
+            #[exit_code(64)]
+            Usage,
+        }
+        ConfigError := {
+            #[exit_code(78)]
+            #[display("failed to read config")]
+            Read(std::io::Error),
+            Invalid,
+        }
+        AppError := CliError || ConfigError || { Interrupted }
+        ParseError := {
+            InvalidHeader,
+        }
+    }
+
+    impl ParseError {
+        /// Sets without `#[exit_code(..)]` get no generated `exit_code`, so one can be written by hand
+        pub fn exit_code(&self) -> u8 {
+            65
+        }
+    }
+
+    #[test]
+    fn exit_code() {
+        assert_eq!(
+            CliError::MissingArgument {
+                name: "path".to_string()
+            }
+            .exit_code(),
+            2
+        );
+        assert_eq!(CliError::Usage.exit_code(), 64);
+        assert_eq!(
+            ConfigError::Read(std::io::Error::other("denied")).exit_code(),
+            78
+        );
+        assert_eq!(ConfigError::Invalid.exit_code(), 1);
+        assert_eq!(ParseError::InvalidHeader.exit_code(), 65);
+    }
+
+    #[test]
+    fn kept_across_aggregation() {
+        assert_eq!(AppError::from(CliError::Usage).exit_code(), 64);
+        assert_eq!(
+            AppError::from(CliError::MissingArgument {
+                name: "path".to_string()
+            })
+            .exit_code(),
+            2
+        );
+        assert_eq!(
+            AppError::from(ConfigError::Read(std::io::Error::other("denied"))).exit_code(),
+            78
+        );
+        assert_eq!(AppError::Interrupted.exit_code(), 1);
+        assert_eq!(ToExitCode::exit_code(&AppError::Usage), 64);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn exit() {
+        use error_set::Exit;
+        use std::process::{ExitCode, Termination};
+
+        let ok: Exit<AppError> = Ok(()).into();
+        assert_eq!(ok.report(), ExitCode::SUCCESS);
+        let error: Exit<AppError> = Err(AppError::Usage).into();
+        assert_eq!(error.report(), ExitCode::from(64));
+        let error: Exit<AppError> = AppError::Interrupted.into();
+        assert_eq!(error.report(), ExitCode::FAILURE);
+    }
+}
+
+#[cfg(test)]
+pub mod io_kind {
+    use std::io::{self, ErrorKind, Read};
+
+    use error_set::error_set;
+
+    error_set! {
+        #[io_kind(InvalidData)]
+        DecodeError := {
+            #[display("invalid header")]
+            InvalidHeader,
+            #[io_kind(UnexpectedEof)]
+            #[display("truncated after {read} bytes")]
+            Truncated { read: usize },
+            #[display("read failed")]
+            Io(io::Error),
+        }
+        ArchiveError := DecodeError || {
+            #[io_kind(NotFound)]
+            #[display("missing entry `{name}`")]
+            MissingEntry { name: String },
+            Locked,
+        }
+    }
+
+    struct Decoder;
+
+    impl Decoder {
+        fn decode(&self) -> Result<usize, DecodeError> {
+            Err(DecodeError::Truncated { read: 3 })
+        }
+    }
+
+    impl Read for Decoder {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Ok(self.decode()?)
+        }
+    }
+
+    #[test]
+    fn io_kind() {
+        let error: io::Error = DecodeError::InvalidHeader.into();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "invalid header");
+        let error: io::Error = DecodeError::Truncated { read: 3 }.into();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        let error: io::Error = ArchiveError::Locked.into();
+        assert_eq!(error.kind(), ErrorKind::Other);
+        let error: io::Error = ArchiveError::MissingEntry {
+            name: "a.txt".to_string(),
+        }
+        .into();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn kept_across_aggregation() {
+        let error: io::Error = ArchiveError::from(DecodeError::InvalidHeader).into();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let error: io::Error = ArchiveError::from(DecodeError::Truncated { read: 3 }).into();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn io_source_returned_as_is() {
+        let error: io::Error =
+            DecodeError::Io(io::Error::new(ErrorKind::PermissionDenied, "denied")).into();
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(error.to_string(), "denied");
+        assert!(
+            error
+                .get_ref()
+                .and_then(|e| e.downcast_ref::<DecodeError>())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn recoverable() {
+        let error = Decoder.read(&mut [0; 8]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        let decode_error = error
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<DecodeError>())
+            .unwrap();
+        assert!(matches!(decode_error, DecodeError::Truncated { read: 3 }));
+        let decode_error = *error
+            .into_inner()
+            .unwrap()
+            .downcast::<DecodeError>()
+            .unwrap();
+        assert!(matches!(decode_error, DecodeError::Truncated { read: 3 }));
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "tracing")]
+pub mod tracing {
+    use error_set::error_set;
+    use tracing_test::traced_test;
+
+    error_set! {
+        FetchError := {
+            #[display("timed out after {secs}s")]
+            #[level(warn)]
+            Timeout {
+                secs: u64,
+            },
+            #[display("unauthorized for {user}")]
+            #[level(info)]
+            Unauthorized {
+                user: String,
+                #[redact]
+                token: String,
+            },
+            NotFound,
+            #[display("io failure")]
+            #[level(debug)]
+            Io(std::io::Error),
+        }
+        SyncError := FetchError || { Conflict }
+        ParseError := { Empty }
+        ConfigError := ParseError || { Missing }
+    }
+
+    // Sets without `#[level(..)]` do not get `level` or `trace`, so they can have their own.
+    impl ParseError {
+        pub fn level(&self) -> u8 {
+            1
+        }
+    }
+
+    #[test]
+    fn level() {
+        assert_eq!(
+            FetchError::Timeout { secs: 3 }.level(),
+            tracing::Level::WARN
+        );
+        assert_eq!(FetchError::NotFound.level(), tracing::Level::ERROR);
+        assert_eq!(
+            FetchError::Io(std::io::Error::other("disk")).level(),
+            tracing::Level::DEBUG
+        );
+        assert_eq!(
+            SyncError::from(FetchError::Timeout { secs: 3 }).level(),
+            tracing::Level::WARN
+        );
+        assert_eq!(SyncError::Conflict.level(), tracing::Level::ERROR);
+    }
+
+    #[test]
+    #[traced_test]
+    fn trace() {
+        FetchError::Timeout { secs: 3 }.trace();
+        assert!(logs_contain(
+            "WARN trace: r#mod::tracing: timed out after 3s code=\"FetchError::Timeout\" secs=3"
+        ));
+
+        FetchError::Unauthorized {
+            user: "alice".to_string(),
+            token: "hunter2".to_string(),
+        }
+        .trace();
+        assert!(logs_contain(
+            "unauthorized for alice code=\"FetchError::Unauthorized\" user=\"alice\" token=\"<redacted>\""
+        ));
+        assert!(!logs_contain("hunter2"));
+
+        FetchError::Io(std::io::Error::other("disk")).trace();
+        assert!(logs_contain(
+            "io failure code=\"FetchError::Io\" source=disk"
+        ));
+
+        SyncError::Conflict.trace();
+        assert!(logs_contain("Conflict code=\"SyncError::Conflict\""));
+    }
+
+    #[test]
+    #[traced_test]
+    fn trace_conversion() {
+        assert_eq!(ParseError::Empty.level(), 1);
+        let _ = ConfigError::from(ParseError::Empty);
+        assert!(logs_contain(
+            "TRACE trace_conversion: r#mod::tracing: error converted from=\"ParseError\" to=\"ConfigError\""
+        ));
+    }
+}
+
+#[cfg(test)]
+pub mod report {
+    use error_set::{Report, ReportStyle, error_set, write_report};
+
+    error_set! {
+        LoadError := {
+            #[display("failed to load")]
+            Config(ConfigError),
+        }
+        ConfigError := {
+            #[display("failed to read config")]
+            Read(std::io::Error),
+            Empty,
+        }
+    }
+
+    fn load() -> Result<(), Report<LoadError>> {
+        Err(LoadError::Config(ConfigError::Read(std::io::Error::other(
+            "permission denied\nreadonly filesystem",
+        ))))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test() {
+        let report = load().unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "failed to load: failed to read config: permission denied\nreadonly filesystem"
+        );
+        // A backtrace follows when captured
+        assert!(format!("{:?}", report).starts_with(
+            "failed to load\n\nCaused by:\n    0: failed to read config\n    1: permission denied\n       readonly filesystem"
+        ));
+        let report = report.pretty();
+        assert_eq!(report.to_string(), format!("{:?}", report));
+        assert!(matches!(report.into_error(), LoadError::Config(_)));
+
+        let report =
+            Report::new(LoadError::Config(ConfigError::Empty)).style(ReportStyle::MultiLine);
+        assert!(
+            report
+                .to_string()
+                .starts_with("failed to load\n\nCaused by:\n    ConfigError::Empty")
+        );
+
+        let mut out = String::new();
+        write_report(&mut out, &ConfigError::Empty, ReportStyle::MultiLine).unwrap();
+        assert_eq!(out, "ConfigError::Empty");
+    }
+}
+
 #[test]
 #[ignore]
 fn trybuild() {
@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    #[debug(compact)]
+    FetchError := {
+        Timeout,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    ConfigError := {
+        #[display(chain, "failed to read config")]
+        Missing,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use error_set::error_set;
+
+error_set! {
+    #[display(doc)]
+    BookSectionParsingError := {
+        /// Missing field {feild}
+        MissingField {
+            field: String
+        },
+    }
+}
+
+fn main() {}
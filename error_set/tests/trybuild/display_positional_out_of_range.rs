@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    DownloadError := {
+        #[display("Io error: {0}, {1}")]
+        IoError(std::io::Error),
+    }
+}
+
+fn main() {}
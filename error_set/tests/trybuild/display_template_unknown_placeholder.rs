@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    #[display(template = "[{sett}] {message}")]
+    BookError := {
+        MissingBookDescription,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use error_set::error_set;
+
+error_set! {
+    BookSectionParsingError := {
+        #[display("Missing field {feild}")]
+        MissingField {
+            field: String
+        },
+    }
+}
+
+fn main() {}
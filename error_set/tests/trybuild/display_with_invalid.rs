@@ -0,0 +1,12 @@
+use error_set::error_set;
+
+error_set! {
+    UploadError := {
+        #[display(with = "fmt_too_large")]
+        TooLarge {
+            size: u64,
+        },
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    #[exit_code(0)]
+    CliError := {
+        Usage,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    DecodeError := {
+        #[io_kind(Missing)]
+        InvalidHeader,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use error_set::error_set;
+
+error_set! {
+    FetchError := {
+        #[level(fatal)]
+        Timeout,
+    }
+    SyncError := FetchError || { Conflict }
+}
+
+fn main() {}
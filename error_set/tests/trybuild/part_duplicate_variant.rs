@@ -0,0 +1,14 @@
+use error_set::error_set_part;
+
+error_set_part! {
+    #![module(crate::errors)]
+    UploadError := {
+        Timeout,
+        #[display("Uploaded {bytes} bytes")]
+        Timeout {
+            bytes: u64
+        },
+    } || NetworkError
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use error_set::error_set_part;
+
+mod errors {
+    error_set::error_set! {
+        UploadError := {
+            Timeout,
+        }
+        NetworkError := {
+            Offline,
+        }
+    }
+}
+
+error_set_part! {
+    #![module(crate::errors)]
+    UploadError := {
+        Timeout,
+    } || NetworkEror
+}
+
+fn main() {}
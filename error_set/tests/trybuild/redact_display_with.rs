@@ -0,0 +1,19 @@
+use core::fmt;
+
+use error_set::error_set;
+
+error_set! {
+    AuthError := {
+        #[display(with = fmt_locked)]
+        Locked {
+            #[redact]
+            email: String,
+        },
+    }
+}
+
+fn fmt_locked(email: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "account {email} is locked")
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use error_set::error_set;
+
+error_set! {
+    FetchError := {
+        #[retryable(source)]
+        Timeout,
+    }
+}
+
+fn main() {}
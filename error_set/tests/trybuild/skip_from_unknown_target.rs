@@ -0,0 +1,11 @@
+use error_set::error_set;
+
+error_set! {
+    U := {
+        IoError(std::io::Error),
+    }
+    #[skip(From(std::io::Eror))]
+    W := U
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use error_set::error_set;
+
+error_set! {
+    #[strict]
+    TransferError := DownloadError || UploadError
+    DownloadError := {
+        Timeout {
+            secs: u64
+        },
+    }
+    UploadError := {
+        Timeout {
+            ms: u64
+        },
+    }
+}
+
+fn main() {}
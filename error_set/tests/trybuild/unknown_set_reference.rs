@@ -0,0 +1,13 @@
+use error_set::error_set;
+
+error_set! {
+    MediaError := DownloadErorr || BookParsingError
+    DownloadError := {
+        InvalidUrl,
+    }
+    BookParsingError := {
+        MissingBookDescription,
+    }
+}
+
+fn main() {}
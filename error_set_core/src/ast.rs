@@ -0,0 +1,1384 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    Attribute, Ident, ItemStruct, Result, TypeParam, Visibility, braced, parenthesized,
+    parse::{Parse, ParseBuffer, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token::{self, Pub},
+};
+
+const DISPLAY_ATTRIBUTE_NAME: &str = "display";
+const DEBUG_ATTRIBUTE_NAME: &str = "debug";
+const DISABLE_ATTRIBUTE_NAME: &str = "skip";
+const STRICT_ATTRIBUTE_NAME: &str = "strict";
+const EXPLAIN_ATTRIBUTE_NAME: &str = "explain";
+const MODULE_ATTRIBUTE_NAME: &str = "module";
+const REDACT_ATTRIBUTE_NAME: &str = "redact";
+const RETRYABLE_ATTRIBUTE_NAME: &str = "retryable";
+const LEVEL_ATTRIBUTE_NAME: &str = "level";
+const EXIT_CODE_ATTRIBUTE_NAME: &str = "exit_code";
+const IO_KIND_ATTRIBUTE_NAME: &str = "io_kind";
+const DOC_DISPLAY_ARGUMENT: &str = "doc";
+/// `#[display(chain)]` - append the display message of the source
+pub const CHAIN_DISPLAY_ARGUMENT: &str = "chain";
+/// `#[display(with = path::to::fn)]` - display with a function, passed the source and fields followed by the `Formatter`
+pub const WITH_DISPLAY_ARGUMENT: &str = "with";
+
+/// The body of an `error_set!`
+#[derive(Clone)]
+pub struct AstErrorSet {
+    /// The error sets and error structs, in declaration order
+    pub set_items: Vec<AstErrorKind>,
+}
+
+impl Parse for AstErrorSet {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut set_items = Vec::new();
+
+        while !input.is_empty() {
+            let fork = input.fork();
+            let set_item = match input.parse::<AstErrorKind>() {
+                Ok(value) => value,
+                Err(error) => {
+                    if input.is_empty() {
+                        return Err(syn::Error::new(last_token_span(fork), error.to_string()));
+                    } else {
+                        return Err(error);
+                    }
+                }
+            };
+            set_items.push(set_item);
+        }
+        Ok(AstErrorSet { set_items })
+    }
+}
+
+/// The body of an `error_set_part!`. Same as [AstErrorSet] but may start with `#![module(path)]`, the module
+/// the parts are combined into.
+pub struct AstErrorSetPart {
+    /// The path of `#![module(path)]`
+    pub module: Option<syn::Path>,
+    /// The `#![module(path)]` itself, which is not part of the combined `error_set!`
+    pub module_attribute: Option<Attribute>,
+    /// The part without `#![module(path)]`
+    pub error_set: AstErrorSet,
+}
+
+impl AstErrorSetPart {
+    /// Parses the leading `#![module(path)]` of a part, if any, leaving the rest unparsed.
+    pub fn parse_module_attribute(input: ParseStream) -> Result<Option<(Attribute, syn::Path)>> {
+        let mut module = None;
+        for attribute in input.call(Attribute::parse_inner)? {
+            if !attribute.path().is_ident(MODULE_ATTRIBUTE_NAME) || module.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attribute,
+                    format!("Only a single `#![{MODULE_ATTRIBUTE_NAME}(path)]` is supported here."),
+                ));
+            }
+            let path = attribute.parse_args::<syn::Path>()?;
+            module = Some((attribute, path));
+        }
+        Ok(module)
+    }
+}
+
+impl Parse for AstErrorSetPart {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (module_attribute, module) = input.call(Self::parse_module_attribute)?.unzip();
+        let error_set = input.parse::<AstErrorSet>()?;
+        Ok(AstErrorSetPart {
+            module,
+            module_attribute,
+            error_set,
+        })
+    }
+}
+
+/// An item declared in an `error_set!`
+#[derive(Clone)]
+pub enum AstErrorKind {
+    /// e.g. `struct AuthError { user: String }`
+    Struct(AstErrorStruct),
+    /// e.g. `MediaError := DownloadError || { Parse }`
+    Enum(AstErrorEnumDeclaration),
+}
+
+impl Parse for AstErrorKind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        let _ = fork.call(Attribute::parse_outer);
+        let _ = fork.parse::<Visibility>();
+        if fork.peek(syn::Token![struct]) {
+            let error_struct = input.parse::<AstErrorStruct>()?;
+            return Ok(AstErrorKind::Struct(error_struct));
+        }
+        let enum_decl = input.parse::<AstErrorEnumDeclaration>()?;
+        return Ok(AstErrorKind::Enum(enum_decl));
+    }
+}
+
+/// An error struct, e.g. `struct AuthError { user: String }`
+#[derive(Clone)]
+pub struct AstErrorStruct {
+    /// The struct as declared, without the attributes handled by `error_set!`
+    pub r#struct: ItemStruct,
+    /// `#[display("..")]` - the format string of the generated `Display`
+    pub display: Option<DisplayAttribute>,
+    /// `#[display(chain, ..)]` - the display message of the source is appended
+    pub chain: bool,
+    /// Fields marked `#[redact]`, shown as `<redacted>` in the generated `Debug` and `Display`
+    pub redacted_fields: Vec<Ident>,
+}
+
+impl Parse for AstErrorStruct {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut item_struct = input.parse::<ItemStruct>()?;
+        for field in &item_struct.fields {
+            if field.ident.is_none() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "Tuple structs are not currently supported, use named fields.", // todo
+                ));
+            }
+        }
+        match item_struct.vis {
+            syn::Visibility::Public(_) => {}
+            syn::Visibility::Restricted(_) => {}
+            syn::Visibility::Inherited => {
+                item_struct.vis = syn::Visibility::Public(Pub {
+                    span: item_struct.vis.span(),
+                })
+            }
+        };
+        let mut redacted_fields = Vec::new();
+        for field in item_struct.fields.iter_mut() {
+            if extract_flag_attribute(&mut field.attrs, REDACT_ATTRIBUTE_NAME)? {
+                redacted_fields.push(field.ident.clone().unwrap());
+            }
+        }
+        let (mut display, chain) = split_chain(extract_display_attribute(&mut item_struct.attrs)?);
+        if display.as_ref().is_some_and(is_doc_display) {
+            display = doc_display(&item_struct.attrs);
+        }
+        Ok(AstErrorStruct {
+            r#struct: item_struct,
+            display,
+            chain,
+            redacted_fields,
+        })
+    }
+}
+
+/// An error set declaration before resolving, e.g. `MediaError := DownloadError || { Parse }`
+#[derive(Clone)]
+pub struct AstErrorEnumDeclaration {
+    /// Attributes passed through to the generated enum, e.g. docs and derives
+    pub attributes: Vec<Attribute>,
+    /// The visibility of the generated enum
+    pub vis: Visibility,
+    /// The name of the set, e.g. `MediaError`
+    pub error_name: Ident,
+    /// The generic parameters of the set, e.g. `T: Debug` in `ParseError<T: Debug>`
+    pub generics: Vec<TypeParam>,
+    /// `#[skip(..)]` - the trait implementations not generated
+    pub disabled: Disabled,
+    /// `#[strict]` - same-named variants with different shapes in other sets are an error
+    pub strict: bool,
+    /// `#[explain]` - generate an `EXPLAIN` const describing the `From` implementations
+    pub explain: bool,
+    /// `#[display(..)]` - how the variants of the set are displayed
+    pub display_policy: Option<DisplayPolicy>,
+    /// `#[debug(display)]` - `Debug` is the variant name followed by the display message
+    pub debug_display: bool,
+    /// The parts joined with `||`, in declaration order
+    pub parts: Vec<AstInlineOrRefError>,
+}
+
+impl Parse for AstErrorEnumDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attributes = input.call(Attribute::parse_outer)?;
+        let disabled = extract_disabled(&mut attributes)?;
+        let strict = extract_flag_attribute(&mut attributes, STRICT_ATTRIBUTE_NAME)?;
+        let explain = extract_flag_attribute(&mut attributes, EXPLAIN_ATTRIBUTE_NAME)?;
+        let display_policy = extract_display_attribute(&mut attributes)?
+            .map(|e| parse_display_policy(&e))
+            .transpose()?;
+        let debug_display = extract_debug_attribute(&mut attributes)?
+            .map(|e| parse_debug_policy(&e))
+            .transpose()?
+            .unwrap_or(false);
+        let exit_code = extract_exit_code_attribute(&mut attributes)?;
+        let io_kind = extract_io_kind_attribute(&mut attributes)?;
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                input.span(),
+                "Expected an error definition to be next after attributes. You may have a dangling doc comment.",
+            ));
+        }
+        let vis = if input.peek(syn::Token![pub]) {
+            input.parse::<Visibility>()?
+        } else {
+            Visibility::Public(Pub { span: input.span() })
+        };
+        if input.peek(syn::Token![enum]) {
+            input.parse::<syn::Token![enum]>().unwrap();
+        }
+        let error_name: Ident = input.parse()?;
+        let generics = if input.peek(syn::Token![<]) {
+            generics(&input)? // todo improve generic parsing to include where clauses
+        } else {
+            Vec::new()
+        };
+        let last_position_save = input.fork();
+        // enum set
+        if input.peek(syn::Token![:]) && input.peek2(syn::Token![=]) {
+            input.parse::<syn::Token![:]>().unwrap();
+            input.parse::<syn::Token![=]>().unwrap();
+            let mut parts = Vec::new();
+            while !input.is_empty() {
+                let part = input.parse::<AstInlineOrRefError>()?;
+                parts.push(part);
+                if input.peek(token::OrOr) {
+                    input.parse::<token::OrOr>().unwrap();
+                    continue;
+                } else {
+                    break;
+                }
+            }
+            if parts.is_empty() {
+                return Err(syn::Error::new(
+                    last_position_save.span(),
+                    "Missing error definitions",
+                ));
+            }
+            // Dev Note: Resolved here so the exit code and io kind are part of the variant when it is aggregated
+            // into other sets
+            for part in &mut parts {
+                if let AstInlineOrRefError::Inline(inline_error) = part {
+                    for error_variant in inline_error.error_variants.iter_mut() {
+                        if let Some(exit_code) = exit_code {
+                            error_variant.exit_code.get_or_insert(exit_code);
+                        }
+                        if let Some(io_kind) = &io_kind {
+                            error_variant.io_kind.get_or_insert_with(|| io_kind.clone());
+                        }
+                    }
+                }
+            }
+            // Dev Note: The policy is part of the variant, so it is kept when the variant is aggregated into other
+            // sets. The display of `doc` is also resolved here, so its placeholders are validated where it is written.
+            if let Some(display_policy) = &display_policy {
+                for part in &mut parts {
+                    if let AstInlineOrRefError::Inline(inline_error) = part {
+                        for error_variant in inline_error.error_variants.iter_mut() {
+                            if display_policy.doc && error_variant.display.is_none() {
+                                error_variant.display = doc_display(&error_variant.attributes);
+                            }
+                            error_variant.display_policy = Some(display_policy.clone());
+                        }
+                    }
+                }
+            }
+            return Ok(AstErrorEnumDeclaration {
+                attributes,
+                vis,
+                error_name,
+                generics,
+                disabled,
+                strict,
+                explain,
+                display_policy,
+                debug_display,
+                parts,
+            });
+        // normal enum
+        } else if input.peek(syn::token::Brace) {
+            return Err(syn::Error::new(
+                last_position_save.span(),
+                "Expected set declartion (`:=`), got a `{`. If attempting to declare a normal enum, these are not currently supported, only error set enums.",
+            ));
+        } else {
+            return Err(syn::Error::new(
+                last_position_save.span(),
+                "Expected set declartion (`:=`) to be next.",
+            ));
+        }
+    }
+}
+
+/// A part of an error set declaration, joined with `||`
+#[derive(Clone)]
+pub enum AstInlineOrRefError {
+    /// e.g. `{ Parse }`
+    Inline(AstInlineError),
+    /// e.g. `DownloadError`
+    Ref(RefError),
+}
+
+impl Parse for AstInlineOrRefError {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(token::Brace) {
+            return match input.parse::<AstInlineError>() {
+                Ok(inline_error) => Ok(AstInlineOrRefError::Inline(inline_error)),
+                Err(err) => Err(err),
+            };
+        }
+        if input.peek(Ident) {
+            return match input.parse::<RefError>() {
+                Ok(ref_error) => Ok(AstInlineOrRefError::Ref(ref_error)),
+                Err(err) => Err(err),
+            };
+        }
+        return Err(syn::parse::Error::new(
+            input.span(),
+            "Expected the next token to be the start of an inline error variant ('{...}') or a reference to another error enum.",
+        ));
+    }
+}
+
+/// Variants declared inline, e.g. `{ Parse, IoError(std::io::Error) }`
+#[derive(Clone)]
+pub struct AstInlineError {
+    /// The variants, in declaration order
+    pub error_variants: Punctuated<AstErrorVariant, token::Comma>,
+}
+
+impl Parse for AstInlineError {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let save_position = input.fork();
+        let _brace_token = braced!(content in input);
+        let error_variants = content.parse_terminated(
+            |input: ParseStream| input.parse::<AstErrorVariant>(),
+            token::Comma,
+        )?;
+        if error_variants.is_empty() {
+            return Err(syn::parse::Error::new(
+                save_position.span(),
+                "Inline error variants cannot be empty",
+            ));
+        }
+        return Ok(AstInlineError { error_variants });
+    }
+}
+
+/// A reference to another error set, e.g. `DownloadError` in `MediaError := DownloadError || { Parse }`
+#[derive(Clone)]
+pub struct RefError {
+    /// The name of the referenced set
+    pub name: Ident,
+    /// The generic arguments of the reference, e.g. `T` in `ParseError<T>`
+    pub generic_refs: Vec<Ident>,
+}
+
+impl Parse for RefError {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<Ident>()?;
+        let generics = generics(&input)?;
+        Ok(RefError {
+            name,
+            generic_refs: generics,
+        })
+    }
+}
+
+//************************************************************************//
+
+/// A variant for an error
+#[derive(Clone)]
+pub struct AstErrorVariant {
+    /// Attributes passed through to the generated variant, e.g. docs
+    pub attributes: Vec<Attribute>,
+    /// `#[cfg(..)]` attributes, also applied to the generated code for the variant
+    pub cfg_attributes: Vec<Attribute>,
+    /// `#[display("..")]` - the format string of the variant
+    pub display: Option<DisplayAttribute>,
+    /// The `#[display(..)]` of the set it is declared in, kept when aggregated into sets without one
+    pub display_policy: Option<DisplayPolicy>,
+    /// `#[display(chain, ..)]` - the display message of the source is appended
+    pub chain: bool,
+    /// `#[debug("..")]` - the format string to use for `Debug`
+    pub debug: Option<DisplayAttribute>,
+    /// `#[retryable]` - whether the variant is retryable, see [RetryableAttribute]
+    pub retryable: Option<RetryableAttribute>,
+    /// `#[level(warn)]` - the `tracing` level of the variant
+    pub level: Option<LevelAttribute>,
+    /// `#[exit_code(2)]` - the process exit code of the variant, or of the set's variants if on the set
+    pub exit_code: Option<u8>,
+    /// `#[io_kind(NotFound)]` - the `std::io::ErrorKind` of the variant, or of the set's variants if on the set
+    pub io_kind: Option<Ident>,
+    /// The name of the variant
+    pub name: Ident,
+    // Dev Note: `Some(Vec::new())` == `{}`, `Some(Vec::new(..))` == `{..}`, `None` == ``. `{}` means inline struct if has source as well.
+    /// The fields of an inline struct variant, e.g. `{ secs: u64 }`
+    pub fields: Option<Vec<AstInlineErrorVariantField>>,
+    /// The type of the source, e.g. `std::io::Error` in `IoError(std::io::Error)`
+    pub source_type: Option<syn::TypePath>,
+}
+
+impl Parse for AstErrorVariant {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attributes = input.call(Attribute::parse_outer)?;
+        let (mut attributes, cfg_attributes) = extract_cfg(attributes);
+        let (display, chain) = split_chain(extract_display_attribute(&mut attributes)?);
+        let debug = extract_debug_attribute(&mut attributes)?;
+        let retryable = extract_retryable_attribute(&mut attributes)?;
+        let level = extract_level_attribute(&mut attributes)?;
+        let exit_code = extract_exit_code_attribute(&mut attributes)?;
+        let io_kind = extract_io_kind_attribute(&mut attributes)?;
+        let mut name = input.parse::<Ident>().ok();
+        let mut source_type = None;
+        let source_content: syn::Result<_> = (|| {
+            let content;
+            parenthesized!(content in input);
+            return Ok(content);
+        })();
+        if let Ok(content) = source_content {
+            let source = content.parse_terminated(
+                |input: ParseStream| input.parse::<syn::TypePath>(),
+                token::Comma,
+            );
+            if let Ok(source) = source {
+                if source.len() == 1 {
+                    let mut source = source.into_iter();
+                    source_type = source.next();
+                    // shorthand syntax used - `(SourceName)` instead of `SourceName(SourceName)`
+                    if name.is_none() {
+                        fn capitalize(s: &str) -> String {
+                            let mut c = s.chars();
+                            match c.next() {
+                                None => String::new(),
+                                Some(first) => {
+                                    first.to_uppercase().collect::<String>() + c.as_str()
+                                }
+                            }
+                        }
+                        let mut parts = Vec::new();
+                        let source_type = source_type.as_ref().unwrap();
+                        for part in &source_type.path.segments {
+                            parts.push(capitalize(&*part.ident.to_string()))
+                        }
+                        name = Some(Ident::new(&parts.join(""), source_type.span()));
+                    }
+                } else {
+                    return Err(syn::parse::Error::new(
+                        source.span(),
+                        format!(
+                            "Expected one element - a source error type. Recieved {}.",
+                            source.len()
+                        ),
+                    ));
+                }
+            }
+        }
+        let name = match name {
+            Some(name) => name,
+            None => {
+                return Err(syn::parse::Error::new(
+                    input.span(),
+                    "Expected an error variant name (`ErrorVariant`) or a wrapper shorthand (`(ErrorVariant)`).",
+                ));
+            }
+        };
+        let field_content: syn::Result<_> = (|| {
+            let content;
+            syn::braced!(content in input);
+            return Ok(content);
+        })();
+        let content = match field_content {
+            Err(_) => {
+                return Ok(AstErrorVariant {
+                    attributes,
+                    cfg_attributes,
+                    display,
+                    display_policy: None,
+                    chain,
+                    debug,
+                    retryable,
+                    level,
+                    exit_code,
+                    io_kind,
+                    name,
+                    fields: None,
+                    source_type,
+                });
+            }
+            Ok(content) => content,
+        };
+        let fields = content
+            .parse_terminated(AstInlineErrorVariantField::parse, syn::Token![,])?
+            .into_iter()
+            .collect::<Vec<_>>();
+        let fields = Some(fields);
+        Ok(AstErrorVariant {
+            attributes,
+            cfg_attributes,
+            display,
+            display_policy: None,
+            chain,
+            debug,
+            retryable,
+            level,
+            exit_code,
+            io_kind,
+            name,
+            fields,
+            source_type,
+        })
+    }
+}
+
+//************************************************************************//
+
+fn generics<T: Parse>(input: &ParseStream) -> Result<Vec<T>> {
+    if input.peek(syn::Token![<]) {
+        input.parse::<syn::Token![<]>()?;
+        let mut generics = Vec::new();
+        loop {
+            let next = input.parse::<T>();
+            match next {
+                Ok(next) => generics.push(next),
+                Err(_) => {}
+            }
+            let punc = input.parse::<syn::Token![,]>();
+            if punc.is_err() {
+                break;
+            }
+        }
+        input.parse::<syn::Token![>]>()?;
+        Ok(generics)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+//************************************************************************//
+
+/// An argument of `#[skip(..)]`, e.g. `From(std::io::Error)`
+#[derive(Clone)]
+pub struct DisableArg {
+    /// The name of the disabled trait, e.g. `From`
+    pub name: Ident,
+    /// The types the trait is disabled for, e.g. `std::io::Error`. Empty disables it for all.
+    pub refs: Vec<syn::TypePath>,
+}
+
+impl Parse for DisableArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<Ident>()?;
+        let content: syn::Result<_> = (|| {
+            let content;
+            parenthesized!(content in input);
+            return Ok(content);
+        })();
+        let refs = if let Ok(content) = content {
+            let refs = content
+                .parse_terminated(
+                    |input: ParseStream| input.parse::<syn::TypePath>(),
+                    token::Comma,
+                )
+                .ok();
+            if let Some(refs) = refs {
+                refs.into_iter().collect()
+            } else {
+                Vec::new()
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(DisableArg { name, refs })
+    }
+}
+
+fn extract_disabled(attributes: &mut Vec<Attribute>) -> syn::Result<Disabled> {
+    let mut to_remove = Vec::new();
+    let mut disabled = Disabled::default();
+    for (i, e) in attributes.iter().enumerate() {
+        let this_disabled = extract_disabled_helper(e)?;
+        if let Some(this_disabled) = this_disabled {
+            disabled.merge(this_disabled);
+            to_remove.push(i);
+        }
+    }
+
+    if to_remove.is_empty() {
+        return Ok(disabled);
+    }
+    let mut index = 0;
+    attributes.retain(|_| {
+        let retain = !&to_remove.contains(&index);
+        index += 1;
+        return retain;
+    });
+
+    Ok(disabled)
+}
+
+fn extract_disabled_helper(attribute: &Attribute) -> syn::Result<Option<Disabled>> {
+    return match &attribute.meta {
+        syn::Meta::Path(_) => Ok(None),
+        syn::Meta::NameValue(_) => Ok(None),
+        syn::Meta::List(list) => {
+            let ident = list.path.get_ident();
+            let Some(ident) = ident else {
+                return Ok(None);
+            };
+            let ident = ident.to_string();
+            if &*ident != DISABLE_ATTRIBUTE_NAME {
+                return Ok(None);
+            }
+
+            let punc = match syn::parse::Parser::parse2(
+                &|input: ParseStream| {
+                    Punctuated::<DisableArg, token::Comma>::parse_terminated(input)
+                },
+                list.tokens.clone(),
+            ) {
+                Ok(okay) => okay,
+                Err(_) => {
+                    return Err(syn::parse::Error::new(
+                        list.tokens.span(),
+                        format!("Invalid syntax for `{}` attribute.", DISABLE_ATTRIBUTE_NAME),
+                    ));
+                }
+            };
+            let mut from = None;
+            let mut display = false;
+            let mut debug = false;
+            let mut error = false;
+            for DisableArg { name, refs } in punc {
+                let ident = name.to_string();
+                match &*ident {
+                    "From" => {
+                        from = Some(refs);
+                    }
+                    "Display" => {
+                        display = true;
+                        if !refs.is_empty() {
+                            return Err(syn::parse::Error::new(
+                                name.span(),
+                                format!(
+                                    "`Display` does not take any arguments for `{}` attribute.",
+                                    DISABLE_ATTRIBUTE_NAME
+                                ),
+                            ));
+                        }
+                    }
+                    "Debug" => {
+                        debug = true;
+                        if !refs.is_empty() {
+                            return Err(syn::parse::Error::new(
+                                name.span(),
+                                format!(
+                                    "`Debug` does not take any arguments for `{}` attribute.",
+                                    DISABLE_ATTRIBUTE_NAME
+                                ),
+                            ));
+                        }
+                    }
+                    "Error" => {
+                        error = true;
+                        if !refs.is_empty() {
+                            return Err(syn::parse::Error::new(
+                                name.span(),
+                                format!(
+                                    "`Error` does not take any arguments for `{}` attribute.",
+                                    DISABLE_ATTRIBUTE_NAME
+                                ),
+                            ));
+                        }
+                    }
+                    _ => {
+                        return Err(syn::parse::Error::new(
+                            ident.span(),
+                            format!(
+                                "`{ident}` is not a valid option for `{DISABLE_ATTRIBUTE_NAME}`"
+                            ),
+                        ));
+                    }
+                }
+            }
+            Ok(Some(Disabled {
+                from,
+                display,
+                debug,
+                error,
+            }))
+        }
+    };
+}
+
+/// The trait implementations disabled with `#[skip(..)]`
+#[derive(Clone)]
+pub struct Disabled {
+    /// `None` == no disabling, `Some` and empty == empty disables all, `Some` and args == only disable args
+    pub from: Option<Vec<syn::TypePath>>,
+    /// Whether `Display` is not generated
+    pub display: bool,
+    /// Whether `Debug` is not generated
+    pub debug: bool,
+    /// Whether `Error` is not generated
+    pub error: bool,
+}
+
+impl Disabled {
+    fn merge(&mut self, other: Disabled) {
+        self.from = other.from;
+        self.display = other.display;
+        self.debug = other.debug;
+        self.error = other.error;
+    }
+}
+
+impl Default for Disabled {
+    fn default() -> Self {
+        Disabled {
+            from: None,
+            display: false,
+            debug: false,
+            error: false,
+        }
+    }
+}
+
+//************************************************************************//
+
+/// Removes attributes like `#[strict]` that take no arguments, returning whether it was present
+fn extract_flag_attribute(attributes: &mut Vec<Attribute>, name: &str) -> syn::Result<bool> {
+    let mut present = false;
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(name) {
+            return true;
+        }
+        if let Err(err) = attribute.meta.require_path_only() {
+            error = Some(syn::parse::Error::new(
+                err.span(),
+                format!("`{name}` does not take any arguments."),
+            ));
+        }
+        present = true;
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(present),
+    }
+}
+
+//************************************************************************//
+
+/// If a variant is transient, so the operation that caused it may succeed if retried
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RetryableAttribute {
+    /// `#[retryable]`
+    Always,
+    /// `#[retryable(source)]` - retryable if the source is, through `error_set::Retryable`
+    Source,
+}
+
+fn extract_retryable_attribute(
+    attributes: &mut Vec<Attribute>,
+) -> syn::Result<Option<RetryableAttribute>> {
+    let mut retryable = None;
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(RETRYABLE_ATTRIBUTE_NAME) {
+            return true;
+        }
+        let this_retryable = match &attribute.meta {
+            syn::Meta::Path(_) => Ok(RetryableAttribute::Always),
+            syn::Meta::List(list)
+                if syn::parse2::<Ident>(list.tokens.clone()).is_ok_and(|e| e == "source") =>
+            {
+                Ok(RetryableAttribute::Source)
+            }
+            meta => Err(syn::parse::Error::new_spanned(
+                meta,
+                format!(
+                    "Expected `#[{RETRYABLE_ATTRIBUTE_NAME}]` or `#[{RETRYABLE_ATTRIBUTE_NAME}(source)]`."
+                ),
+            )),
+        };
+        match this_retryable {
+            Ok(_) if retryable.is_some() => {
+                error = Some(syn::parse::Error::new_spanned(
+                    attribute,
+                    format!("More than one `{RETRYABLE_ATTRIBUTE_NAME}` attribute found"),
+                ));
+            }
+            Ok(this_retryable) => retryable = Some(this_retryable),
+            Err(err) => error = Some(err),
+        }
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(retryable),
+    }
+}
+
+//************************************************************************//
+
+/// The `tracing` level a variant is traced at, e.g. `#[level(warn)]`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LevelAttribute {
+    /// `#[level(trace)]`
+    Trace,
+    /// `#[level(debug)]`
+    Debug,
+    /// `#[level(info)]`
+    Info,
+    /// `#[level(warn)]`
+    Warn,
+    /// `#[level(error)]`
+    Error,
+}
+
+fn extract_level_attribute(attributes: &mut Vec<Attribute>) -> syn::Result<Option<LevelAttribute>> {
+    let mut level = None;
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(LEVEL_ATTRIBUTE_NAME) {
+            return true;
+        }
+        let this_level = match &attribute.meta {
+            syn::Meta::List(list) => match syn::parse2::<Ident>(list.tokens.clone()) {
+                Ok(e) if e == "trace" => Ok(LevelAttribute::Trace),
+                Ok(e) if e == "debug" => Ok(LevelAttribute::Debug),
+                Ok(e) if e == "info" => Ok(LevelAttribute::Info),
+                Ok(e) if e == "warn" => Ok(LevelAttribute::Warn),
+                Ok(e) if e == "error" => Ok(LevelAttribute::Error),
+                _ => Err(syn::parse::Error::new_spanned(
+                    &list.tokens,
+                    "Expected one of `trace`, `debug`, `info`, `warn` or `error`.",
+                )),
+            },
+            meta => Err(syn::parse::Error::new_spanned(
+                meta,
+                format!(
+                    "Expected `#[{LEVEL_ATTRIBUTE_NAME}(..)]`, e.g. `#[{LEVEL_ATTRIBUTE_NAME}(warn)]`."
+                ),
+            )),
+        };
+        match this_level {
+            Ok(_) if level.is_some() => {
+                error = Some(syn::parse::Error::new_spanned(
+                    attribute,
+                    format!("More than one `{LEVEL_ATTRIBUTE_NAME}` attribute found"),
+                ));
+            }
+            Ok(this_level) => level = Some(this_level),
+            Err(err) => error = Some(err),
+        }
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(level),
+    }
+}
+
+//************************************************************************//
+
+fn extract_exit_code_attribute(attributes: &mut Vec<Attribute>) -> syn::Result<Option<u8>> {
+    let mut exit_code = None;
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(EXIT_CODE_ATTRIBUTE_NAME) {
+            return true;
+        }
+        let this_exit_code = attribute
+            .parse_args::<syn::LitInt>()
+            .and_then(|e| e.base10_parse::<u8>())
+            .map_err(|_| {
+                syn::parse::Error::new_spanned(
+                    &attribute.meta,
+                    format!(
+                        "Expected `#[{EXIT_CODE_ATTRIBUTE_NAME}(..)]` with an integer from 1 to 255, e.g. `#[{EXIT_CODE_ATTRIBUTE_NAME}(2)]`."
+                    ),
+                )
+            })
+            .and_then(|e| match e {
+                0 => Err(syn::parse::Error::new_spanned(
+                    &attribute.meta,
+                    "An exit code of `0` means success, so it cannot be used for an error.",
+                )),
+                e => Ok(e),
+            });
+        match this_exit_code {
+            Ok(_) if exit_code.is_some() => {
+                error = Some(syn::parse::Error::new_spanned(
+                    attribute,
+                    format!("More than one `{EXIT_CODE_ATTRIBUTE_NAME}` attribute found"),
+                ));
+            }
+            Ok(this_exit_code) => exit_code = Some(this_exit_code),
+            Err(err) => error = Some(err),
+        }
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(exit_code),
+    }
+}
+
+//************************************************************************//
+
+fn extract_io_kind_attribute(attributes: &mut Vec<Attribute>) -> syn::Result<Option<Ident>> {
+    let mut io_kind = None;
+    let mut error = None;
+    attributes.retain(|attribute| {
+        if !attribute.path().is_ident(IO_KIND_ATTRIBUTE_NAME) {
+            return true;
+        }
+        let this_io_kind = attribute.parse_args::<Ident>().map_err(|_| {
+            syn::parse::Error::new_spanned(
+                &attribute.meta,
+                format!(
+                    "Expected `#[{IO_KIND_ATTRIBUTE_NAME}(..)]` with a variant of `std::io::ErrorKind`, e.g. `#[{IO_KIND_ATTRIBUTE_NAME}(NotFound)]`."
+                ),
+            )
+        });
+        match this_io_kind {
+            Ok(_) if io_kind.is_some() => {
+                error = Some(syn::parse::Error::new_spanned(
+                    attribute,
+                    format!("More than one `{IO_KIND_ATTRIBUTE_NAME}` attribute found"),
+                ));
+            }
+            Ok(this_io_kind) => io_kind = Some(this_io_kind),
+            Err(err) => error = Some(err),
+        }
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(io_kind),
+    }
+}
+
+//************************************************************************//
+
+/// The format string to use for display
+#[derive(Clone)]
+pub struct DisplayAttribute {
+    /// The arguments of the attribute, e.g. `"timed out after {secs}s"`
+    pub tokens: TokenStream,
+}
+
+fn extract_display_attribute(
+    attributes: &mut Vec<Attribute>,
+) -> syn::Result<Option<DisplayAttribute>> {
+    extract_tokens_attribute(attributes, DISPLAY_ATTRIBUTE_NAME)
+}
+
+/// e.g. `#[debug("{name} timed out")]` or `#[debug(display)]`
+fn extract_debug_attribute(
+    attributes: &mut Vec<Attribute>,
+) -> syn::Result<Option<DisplayAttribute>> {
+    extract_tokens_attribute(attributes, DEBUG_ATTRIBUTE_NAME)
+}
+
+fn extract_tokens_attribute(
+    attributes: &mut Vec<Attribute>,
+    name: &str,
+) -> syn::Result<Option<DisplayAttribute>> {
+    let mut to_remove = Vec::new();
+    let mut displays = Vec::new();
+    for (i, e) in attributes.iter().enumerate() {
+        if let Some(display_tokens) = attribute_tokens(e, name) {
+            displays.push(display_tokens);
+            to_remove.push(i);
+        }
+    }
+    if to_remove.is_empty() {
+        return Ok(None);
+    }
+    let display = displays.remove(0);
+    if to_remove.len() > 1 {
+        return Err(syn::parse::Error::new(
+            display.tokens.span(),
+            format!("More than one `{}` attribute found", name),
+        ));
+    }
+
+    if to_remove.is_empty() {
+        return Ok(Some(display));
+    }
+    let mut index = 0;
+    attributes.retain(|_| {
+        let retain = !&to_remove.contains(&index);
+        index += 1;
+        return retain;
+    });
+    Ok(Some(display))
+}
+
+fn attribute_tokens(attribute: &Attribute, name: &str) -> Option<DisplayAttribute> {
+    return match &attribute.meta {
+        syn::Meta::Path(_) => None,
+        syn::Meta::NameValue(_) => None,
+        syn::Meta::List(list) => {
+            let ident = list.path.get_ident()?.to_string();
+            if &*ident == name {
+                return Some(DisplayAttribute {
+                    tokens: list.tokens.clone(),
+                });
+            }
+            return None;
+        }
+    };
+}
+
+/// A set's `#[display(..)]`, e.g. `#[display(doc, humanize)]` or `#[display(template = "[{set}] {message}")]`
+#[derive(Clone, Default)]
+pub struct DisplayPolicy {
+    /// `doc` - the first line of each variant's doc comment is its display message
+    pub doc: bool,
+    /// `humanize` - variants without a display message are displayed as their name in words, e.g.
+    /// `missing book description` for `MissingBookDescription`
+    pub humanize: bool,
+    /// `template = ".."` - wraps the display message of every variant
+    pub template: Option<Vec<DisplayTemplatePart>>,
+    /// `chain` - the display message of the source is appended to the message of each source variant
+    pub chain: bool,
+}
+
+impl DisplayPolicy {
+    /// Combines the policy of a set with the policy a variant inherited from the set it was declared in, option by
+    /// option. The set's template replaces an inherited one, while flags such as `humanize` or `chain` are kept from
+    /// either and apply to the variant when the set is generated.
+    pub fn merged_with(&self, inherited: &DisplayPolicy) -> DisplayPolicy {
+        DisplayPolicy {
+            doc: self.doc || inherited.doc,
+            humanize: self.humanize || inherited.humanize,
+            template: self.template.clone().or_else(|| inherited.template.clone()),
+            chain: self.chain || inherited.chain,
+        }
+    }
+}
+
+/// A part of a `template = ".."`, e.g. `[`, `{set}`, `] ` and `{message}` for `[{set}] {message}`
+#[derive(Clone)]
+pub enum DisplayTemplatePart {
+    /// Text displayed as is
+    Literal(String),
+    /// `{set}` - the name of the set
+    Set,
+    /// `{message}` - the display message of the variant
+    Message,
+}
+
+fn parse_display_policy(display: &DisplayAttribute) -> syn::Result<DisplayPolicy> {
+    let parser = |input: ParseStream| {
+        let mut display_policy = DisplayPolicy::default();
+        while !input.is_empty() {
+            let option = input.parse::<Ident>().map_err(|_| {
+                syn::Error::new(
+                    input.span(),
+                    format!(
+                        "Expected `{DOC_DISPLAY_ARGUMENT}`, `{CHAIN_DISPLAY_ARGUMENT}`, `humanize` or `template = \"..\"` for the `{DISPLAY_ATTRIBUTE_NAME}` attribute of a set."
+                    ),
+                )
+            })?;
+            match &*option.to_string() {
+                DOC_DISPLAY_ARGUMENT => display_policy.doc = true,
+                CHAIN_DISPLAY_ARGUMENT => display_policy.chain = true,
+                "humanize" => display_policy.humanize = true,
+                "template" => {
+                    input.parse::<syn::Token![=]>()?;
+                    let template = input.parse::<syn::LitStr>()?;
+                    display_policy.template = Some(parse_display_template(&template)?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        format!(
+                            "`{option}` is not a valid option for the `{DISPLAY_ATTRIBUTE_NAME}` attribute of a set. Expected `{DOC_DISPLAY_ARGUMENT}`, `{CHAIN_DISPLAY_ARGUMENT}`, `humanize` or `template = \"..\"`."
+                        ),
+                    ));
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<token::Comma>()?;
+            }
+        }
+        Ok(display_policy)
+    };
+    syn::parse::Parser::parse2(parser, display.tokens.clone())
+}
+
+/// A set's `#[debug(..)]`, for which `display` is the only option. Returns if it is set
+fn parse_debug_policy(debug: &DisplayAttribute) -> syn::Result<bool> {
+    match syn::parse2::<Ident>(debug.tokens.clone()) {
+        Ok(ident) if ident == DISPLAY_ATTRIBUTE_NAME => Ok(true),
+        _ => Err(syn::Error::new_spanned(
+            &debug.tokens,
+            format!(
+                "Expected `{DISPLAY_ATTRIBUTE_NAME}` for the `{DEBUG_ATTRIBUTE_NAME}` attribute of a set. Use `#[{DEBUG_ATTRIBUTE_NAME}(\"..\")]` on a variant for a custom format."
+            ),
+        )),
+    }
+}
+
+fn parse_display_template(template: &syn::LitStr) -> syn::Result<Vec<DisplayTemplatePart>> {
+    let value = template.value();
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                let mut is_closed = false;
+                for char in chars.by_ref() {
+                    if char == '}' {
+                        is_closed = true;
+                        break;
+                    }
+                    placeholder.push(char);
+                }
+                let part = match &*placeholder {
+                    _ if !is_closed => None,
+                    "set" => Some(DisplayTemplatePart::Set),
+                    "message" => Some(DisplayTemplatePart::Message),
+                    _ => {
+                        return Err(syn::Error::new(
+                            template.span(),
+                            format!(
+                                "Placeholder `{{{placeholder}}}` in the display template is not available. Available: `{{set}}`, `{{message}}`."
+                            ),
+                        ));
+                    }
+                };
+                let Some(part) = part else {
+                    return Err(syn::Error::new(
+                        template.span(),
+                        "Unclosed `{` in the display template. Use `{{` for a literal `{`.",
+                    ));
+                };
+                if !literal.is_empty() {
+                    parts.push(DisplayTemplatePart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(part);
+            }
+            '}' => {
+                return Err(syn::Error::new(
+                    template.span(),
+                    "Unmatched `}` in the display template. Use `}}` for a literal `}`.",
+                ));
+            }
+            _ => literal.push(char),
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(DisplayTemplatePart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// Separates a leading `chain` from the display message, e.g. `#[display(chain, "failed to read {path}")]`. Returns
+/// the message if any, and whether `chain` is present.
+fn split_chain(display: Option<DisplayAttribute>) -> (Option<DisplayAttribute>, bool) {
+    let Some(display) = display else {
+        return (None, false);
+    };
+    let mut tokens = display.tokens.clone().into_iter();
+    let is_chain = matches!(
+        tokens.next(),
+        Some(proc_macro2::TokenTree::Ident(ident)) if ident == CHAIN_DISPLAY_ARGUMENT
+    );
+    if !is_chain {
+        return (Some(display), false);
+    }
+    match tokens.next() {
+        None => (None, true),
+        Some(proc_macro2::TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+            let tokens = tokens.collect::<TokenStream>();
+            if tokens.is_empty() {
+                (None, true)
+            } else {
+                (Some(DisplayAttribute { tokens }), true)
+            }
+        }
+        _ => (Some(display), false),
+    }
+}
+
+/// `#[display(doc)]`
+fn is_doc_display(display: &DisplayAttribute) -> bool {
+    syn::parse2::<Ident>(display.tokens.clone()).is_ok_and(|e| e == DOC_DISPLAY_ARGUMENT)
+}
+
+/// The first non-empty line of the doc comment as the display message, e.g. `Timed out after {secs}s` for
+/// `/// Timed out after {secs}s`
+/// The first line of the doc comment as a display message, for `#[display(doc)]`
+pub fn doc_display(attributes: &[Attribute]) -> Option<DisplayAttribute> {
+    for attribute in attributes {
+        if !attribute.path().is_ident("doc") {
+            continue;
+        }
+        let syn::Meta::NameValue(name_value) = &attribute.meta else {
+            continue;
+        };
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(doc),
+            ..
+        }) = &name_value.value
+        else {
+            continue;
+        };
+        // Dev Note: Block doc comments are one attribute with multiple lines
+        let doc_value = doc.value();
+        let Some(line) = doc_value.lines().map(str::trim).find(|e| !e.is_empty()) else {
+            continue;
+        };
+        return Some(DisplayAttribute {
+            tokens: syn::LitStr::new(line, doc.span()).into_token_stream(),
+        });
+    }
+    None
+}
+
+/// old and new
+fn extract_cfg(attributes: Vec<Attribute>) -> (Vec<Attribute>, Vec<Attribute>) {
+    let mut to_remove = Vec::new();
+    for (index, attribute) in attributes.iter().enumerate() {
+        match &attribute.meta {
+            syn::Meta::NameValue(_) => {}
+            syn::Meta::Path(_) => {}
+            syn::Meta::List(meta_list) => {
+                if meta_list
+                    .path
+                    .get_ident()
+                    .is_some_and(|e| e == "cfg")
+                {
+                    to_remove.push(index);
+                }
+            }
+        };
+    }
+
+    let mut cfgs = Vec::new();
+    let mut index: usize = usize::MAX;
+    let attributes = attributes
+        .into_iter()
+        .filter_map(|e| {
+            index = index.wrapping_add(1);
+            if to_remove.contains(&index) {
+                cfgs.push(e);
+                return None;
+            }
+            Some(e)
+        })
+        .collect::<Vec<_>>();
+    (attributes, cfgs)
+}
+
+/// A field of an inline struct variant, e.g. `secs: u64`
+#[derive(Clone)]
+pub struct AstInlineErrorVariantField {
+    /// Attributes passed through to the generated field, e.g. docs
+    pub attributes: Vec<Attribute>,
+    /// The name of the field
+    pub name: Ident,
+    /// The type of the field
+    pub r#type: syn::Type,
+    /// `#[redact]` - the value is shown as `<redacted>` in the generated `Debug` and `Display`
+    pub redact: bool,
+}
+
+impl Parse for AstInlineErrorVariantField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attributes = input.call(Attribute::parse_outer)?;
+        let redact = extract_flag_attribute(&mut attributes, REDACT_ATTRIBUTE_NAME)?;
+        let name: Ident = input.parse()?;
+        let _: syn::Token![:] = input.parse()?;
+        let r#type: syn::Type = input.parse()?;
+        Ok(AstInlineErrorVariantField {
+            attributes,
+            name,
+            r#type,
+            redact,
+        })
+    }
+}
+
+impl PartialEq for AstInlineErrorVariantField {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.r#type == other.r#type
+    }
+}
+
+impl Eq for AstInlineErrorVariantField {}
+
+//************************************************************************//
+
+fn last_token_span(input: ParseBuffer) -> proc_macro2::Span {
+    let last_token = input.cursor().token_stream().into_iter().last();
+    let Some(last_token) = last_token else {
+        return proc_macro2::Span::call_site();
+    };
+    match last_token {
+        proc_macro2::TokenTree::Group(group) => group.span_close(),
+        proc_macro2::TokenTree::Ident(ident) => ident.span(),
+        proc_macro2::TokenTree::Punct(punct) => punct.span(),
+        proc_macro2::TokenTree::Literal(literal) => literal.span(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(value: &str) -> syn::Result<Vec<DisplayTemplatePart>> {
+        parse_display_template(&syn::LitStr::new(value, proc_macro2::Span::call_site()))
+    }
+
+    #[test]
+    fn test_parse_display_template() {
+        let parts = template("[{set}] {message}").unwrap();
+        assert!(matches!(
+            &parts[..],
+            [
+                DisplayTemplatePart::Literal(open),
+                DisplayTemplatePart::Set,
+                DisplayTemplatePart::Literal(close),
+                DisplayTemplatePart::Message,
+            ] if open == "[" && close == "] "
+        ));
+    }
+
+    #[test]
+    fn test_parse_display_template_escaped_braces() {
+        let parts = template("{{{message}}}").unwrap();
+        assert!(matches!(
+            &parts[..],
+            [
+                DisplayTemplatePart::Literal(open),
+                DisplayTemplatePart::Message,
+                DisplayTemplatePart::Literal(close),
+            ] if open == "{" && close == "}"
+        ));
+    }
+
+    #[test]
+    fn test_parse_display_template_errors() {
+        let error = |value| template(value).err().unwrap().to_string();
+        assert!(error("{name}").starts_with("Placeholder `{name}`"));
+        assert!(error("{message").starts_with("Unclosed `{`"));
+        assert!(error("message}").starts_with("Unmatched `}`"));
+    }
+}
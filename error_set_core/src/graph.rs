@@ -0,0 +1,415 @@
+use syn::{Attribute, Ident, TypePath};
+
+use crate::model::{
+    Common, ErrorEnum, ErrorVariant, KnownWrapperTypes, is_conversion_target,
+    is_source_only_struct_type, is_source_tuple_type, maybe_extract_known_wrapper_types,
+};
+use crate::suggest::{tokens_to_string, type_path_to_string};
+use crate::validate::variant_shape;
+
+/// Builds the graph of valid `From` conversions between the [ErrorEnum]s.
+pub fn build_graph(error_enums: Vec<ErrorEnum>) -> Vec<ErrorEnumGraphNode> {
+    let mut graph: Vec<ErrorEnumGraphNode> = error_enums
+        .into_iter()
+        .map(ErrorEnumGraphNode::new)
+        .collect();
+
+    // build a graph of valid conversion `From`'s
+    for building_index in 0..graph.len() {
+        'next_enum: for checking_index in 0..graph.len() {
+            if checking_index == building_index {
+                continue;
+            }
+
+            let mut variant_mappings = Vec::new();
+            'look_for_next_variant_match: for (checking_variant_index, checking_variant) in graph
+                [checking_index]
+                .error_enum
+                .error_variants
+                .iter()
+                .enumerate()
+            {
+                for (building_variant_index, building_variant) in graph[building_index]
+                    .error_enum
+                    .error_variants
+                    .iter()
+                    .enumerate()
+                {
+                    if is_conversion_target(checking_variant, building_variant) {
+                        variant_mappings.push((checking_variant_index, building_variant_index));
+                        continue 'look_for_next_variant_match;
+                    }
+                }
+                continue 'next_enum;
+            }
+            graph[building_index]
+                .froms
+                .push((checking_index, variant_mappings));
+        }
+    }
+    graph
+}
+
+/// Determines every `From` implementation that could be generated for this error enum, and if it is not, why.
+pub fn plan_froms<'a>(
+    error_enum_node: &'a ErrorEnumGraphNode,
+    graph: &'a [ErrorEnumGraphNode],
+) -> Vec<FromPlan<'a>> {
+    let error_enum = &error_enum_node.error_enum;
+    let from = &error_enum.disabled.from;
+    let skip_all = from.as_ref().is_some_and(|e| e.is_empty());
+    let temp = Vec::new();
+    let froms_to_disable = from.as_ref().unwrap_or(&temp);
+    let froms_to_disable_idents = froms_to_disable
+        .iter()
+        .flat_map(|e| e.path.get_ident())
+        .collect::<Vec<_>>();
+    let mut from_plans = Vec::new();
+
+    for (from_error_enum, variant_mappings) in error_enum_node.resolved_froms(graph) {
+        let mut all_cfg_attributes = Vec::new();
+        for (from_error_enum_variant, error_enum_variant) in variant_mappings.iter() {
+            for cfg_attribute in from_error_enum_variant
+                .cfg_attributes()
+                .iter()
+                .chain(error_enum_variant.cfg_attributes().iter())
+            {
+                if !all_cfg_attributes.contains(cfg_attribute) {
+                    all_cfg_attributes.push(cfg_attribute.clone());
+                }
+            }
+        }
+        let suppressed =
+            if skip_all || froms_to_disable_idents.contains(&&from_error_enum.error_name) {
+                Some(SuppressedFrom::Skipped)
+            }
+            // Dev Note: If from has generics and they are not the same as target's, then there is no guarantee that `impl_generics`
+            // will contain all of and the correct generics definitions that are for `from_ty_generics`. Merging may cause
+            // conflicts. This guard likely won't ever be removed since the correct mixture of generics may be
+            // impossible to determine without the user explicitly specifying. Even if this guard does not hold,
+            // an "unwanted" (but no compile error) `From` may be generated. This is an edge case and we are
+            // being optimistic, so we don't just not implement `From` for all generics. But a user can opt-out
+            // with `#[disable(From(..))]`
+            else if !from_error_enum.generics.is_empty()
+                && error_enum.generics != from_error_enum.generics
+            {
+                Some(SuppressedFrom::GenericsMismatch)
+            } else {
+                None
+            };
+        from_plans.push(FromPlan {
+            from: FromSource::Set {
+                error_enum: from_error_enum,
+                variant_mappings,
+            },
+            cfg_attributes: all_cfg_attributes,
+            suppressed,
+        });
+    }
+
+    // Sets that would convert if same-named variants had the same shape
+    for other_node in graph {
+        let other = &other_node.error_enum;
+        if other == error_enum
+            || error_enum_node
+                .froms
+                .iter()
+                .any(|(index, _)| graph[*index].error_enum == *other)
+        {
+            continue;
+        }
+        let mut mismatches = Vec::new();
+        let mut has_all_names = true;
+        for other_variant in other.error_variants.iter() {
+            match error_enum
+                .error_variants
+                .iter()
+                .find(|e| e.name() == other_variant.name())
+            {
+                Some(variant) => {
+                    if !is_conversion_target(other_variant, variant) {
+                        mismatches.push((other_variant, variant));
+                    }
+                }
+                None => {
+                    has_all_names = false;
+                    break;
+                }
+            }
+        }
+        if has_all_names && !mismatches.is_empty() {
+            from_plans.push(FromPlan {
+                from: FromSource::Set {
+                    error_enum: other,
+                    variant_mappings: Vec::new(),
+                },
+                cfg_attributes: Vec::new(),
+                suppressed: Some(SuppressedFrom::ShapeMismatch(mismatches)),
+            });
+        }
+    }
+
+    // Do not impl `From` for source where source is the same between multiple variants
+    let mut source_type_to_error_variants: Vec<(&TypePath, Vec<&ErrorVariant>)> = Vec::new();
+    for error_variant in error_enum.error_variants.iter() {
+        if let Some(source_type) = error_variant.source_type() {
+            match source_type_to_error_variants
+                .iter_mut()
+                .find(|(e, _)| *e == source_type)
+            {
+                Some((_, error_variants)) => error_variants.push(error_variant),
+                None => source_type_to_error_variants.push((source_type, vec![error_variant])),
+            }
+        }
+    }
+
+    // Add `From`'s for all valid variants that are wrappers around source errors.
+    for (source_type, error_variants) in source_type_to_error_variants {
+        let error_variant = error_variants[0];
+        let suppressed = if skip_all || froms_to_disable.contains(source_type) {
+            Some(SuppressedFrom::Skipped)
+        } else if error_variants.len() > 1 {
+            Some(SuppressedFrom::DuplicateSourceType(
+                error_variants.iter().map(|e| e.name()).collect(),
+            ))
+        } else if !is_source_tuple_type(error_variant) && !is_source_only_struct_type(error_variant)
+        {
+            Some(SuppressedFrom::SourceStructWithFields)
+        } else {
+            None
+        };
+        let cfg_attributes = error_variant.cfg_attributes().clone();
+        if let Some(KnownWrapperTypes::Box(inner_type)) =
+            maybe_extract_known_wrapper_types(source_type)
+        {
+            from_plans.push(FromPlan {
+                from: FromSource::SourceType {
+                    variant: error_variant,
+                    source_type,
+                },
+                cfg_attributes: cfg_attributes.clone(),
+                suppressed: suppressed.clone(),
+            });
+            from_plans.push(FromPlan {
+                from: FromSource::BoxedSourceType {
+                    variant: error_variant,
+                    inner_type,
+                },
+                cfg_attributes,
+                suppressed,
+            });
+        } else {
+            from_plans.push(FromPlan {
+                from: FromSource::SourceType {
+                    variant: error_variant,
+                    source_type,
+                },
+                cfg_attributes,
+                suppressed,
+            });
+        }
+    }
+    from_plans
+}
+
+/// Describes which `From` implementations are generated for the error enum and which are not, and why.
+pub fn explain(
+    error_enum_node: &ErrorEnumGraphNode,
+    graph: &[ErrorEnumGraphNode],
+) -> String {
+    let error_enum = &error_enum_node.error_enum;
+    let error_enum_name = &error_enum.error_name;
+    let mut generated = Vec::new();
+    let mut suppressed = Vec::new();
+    for from_plan in plan_froms(error_enum_node, graph) {
+        let from = match &from_plan.from {
+            FromSource::Set {
+                error_enum: from_error_enum,
+                ..
+            } => format!("From<{}>", from_error_enum.error_name),
+            FromSource::SourceType {
+                variant,
+                source_type,
+            } => format!(
+                "From<{}> (into `{}`)",
+                type_path_to_string(source_type),
+                variant.name()
+            ),
+            FromSource::BoxedSourceType {
+                variant,
+                inner_type,
+            } => format!(
+                "From<{}> (boxed into `{}`)",
+                type_path_to_string(inner_type),
+                variant.name()
+            ),
+        };
+        match from_plan.suppressed {
+            None => {
+                if from_plan.cfg_attributes.is_empty() {
+                    generated.push(from);
+                } else {
+                    let cfgs = from_plan
+                        .cfg_attributes
+                        .iter()
+                        .map(tokens_to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    generated.push(format!("{from} - only when `{cfgs}`"));
+                }
+            }
+            Some(reason) => {
+                let reason = match reason {
+                    SuppressedFrom::Skipped => "skipped with `#[skip(From(..))]`".to_string(),
+                    SuppressedFrom::GenericsMismatch => {
+                        "the generics of the sets differ".to_string()
+                    }
+                    SuppressedFrom::ShapeMismatch(mismatches) => {
+                        let FromSource::Set {
+                            error_enum: from_error_enum,
+                            ..
+                        } = &from_plan.from
+                        else {
+                            unreachable!("Only sets can have shape mismatches")
+                        };
+                        let mismatches = mismatches
+                            .iter()
+                            .map(|(from_variant, variant)| {
+                                format!(
+                                    "`{}::{}` does not match `{}::{}`",
+                                    from_error_enum.error_name,
+                                    variant_shape(from_variant),
+                                    error_enum_name,
+                                    variant_shape(variant)
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        format!("shape mismatch, {}", mismatches.join(", "))
+                    }
+                    SuppressedFrom::DuplicateSourceType(variant_names) => format!(
+                        "the source type of multiple variants - {}",
+                        variant_names
+                            .iter()
+                            .map(|e| format!("`{e}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    SuppressedFrom::SourceStructWithFields => {
+                        "the source variant has additional fields".to_string()
+                    }
+                };
+                suppressed.push(format!("{from} - {reason}"));
+            }
+        }
+    }
+    let mut explanation = format!("`From` implementations for `{error_enum_name}`\n");
+    for (title, lines) in [("generated", generated), ("not generated", suppressed)] {
+        explanation.push_str(&format!("{title}:\n"));
+        if lines.is_empty() {
+            explanation.push_str("  (none)\n");
+        }
+        for line in lines {
+            explanation.push_str(&format!("  {line}\n"));
+        }
+    }
+    explanation
+}
+
+/// A `From` implementation for an error enum that is either generated or suppressed
+pub struct FromPlan<'a> {
+    /// What is converted from
+    pub from: FromSource<'a>,
+    /// `#[cfg(..)]` attributes of the variants involved, applied to the implementation
+    pub cfg_attributes: Vec<Attribute>,
+    /// `None` if generated
+    pub suppressed: Option<SuppressedFrom<'a>>,
+}
+
+/// What a [FromPlan] converts from
+pub enum FromSource<'a> {
+    /// e.g. `From<SubSet> for Set`
+    Set {
+        /// The subset converted from
+        error_enum: &'a ErrorEnum,
+        /// Each variant of the subset and the variant of the set it converts into
+        variant_mappings: Vec<(&'a ErrorVariant, &'a ErrorVariant)>,
+    },
+    /// e.g. `From<std::io::Error> for Set`
+    SourceType {
+        /// The variant constructed from the source
+        variant: &'a ErrorVariant,
+        /// The source type of the variant
+        source_type: &'a TypePath,
+    },
+    /// e.g. `From<std::io::Error> for Set` where the source type is `Box<std::io::Error>`
+    BoxedSourceType {
+        /// The variant constructed from the boxed source
+        variant: &'a ErrorVariant,
+        /// The type inside the `Box`
+        inner_type: &'a TypePath,
+    },
+}
+
+/// Why a [FromPlan] is not generated
+#[derive(Clone)]
+pub enum SuppressedFrom<'a> {
+    /// `#[skip(From)]` or `#[skip(From(..))]`
+    Skipped,
+    /// The generics of the from set differ from this set
+    GenericsMismatch,
+    /// Every variant name of the from set is in this set, but some do not have the same shape. `(from, this)`
+    ShapeMismatch(Vec<(&'a ErrorVariant, &'a ErrorVariant)>),
+    /// More than one variant wraps the same source type, so it is ambiguous which one to use
+    DuplicateSourceType(Vec<&'a Ident>),
+    /// A source struct with fields cannot be constructed from the source alone
+    SourceStructWithFields,
+}
+//************************************************************************//
+/// A resolved error enum and the error enums that can be converted into it
+#[derive(Clone)]
+pub struct ErrorEnumGraphNode {
+    /// The error enum of the node
+    pub error_enum: ErrorEnum,
+    /// nodes where this error enum can be converted to the other error enum
+    /// 0: index of target enum in graph
+    /// 1: variant mapping
+    ///   0: the from's error_variants's index
+    ///   1: this's error_variants's index
+    pub froms: Vec<(usize, Vec<(usize, usize)>)>,
+}
+
+impl PartialEq for ErrorEnumGraphNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.error_enum == other.error_enum
+    }
+}
+
+impl ErrorEnumGraphNode {
+    /// Creates a node without any conversions
+    pub fn new(node: ErrorEnum) -> ErrorEnumGraphNode {
+        ErrorEnumGraphNode {
+            error_enum: node,
+            froms: Vec::new(),
+        }
+    }
+
+    /// Returns an iterator of all the froms of this error enum. And the variant mappings from this to that.
+    pub fn resolved_froms<'a>(
+        &'a self,
+        graph: &'a [ErrorEnumGraphNode],
+    ) -> impl Iterator<Item = (&'a ErrorEnum, Vec<(&'a ErrorVariant, &'a ErrorVariant)>)> {
+        self.froms.iter().map(|e| {
+            let from = &graph[e.0];
+            let variant_mappings =
+                e.1.iter()
+                    .map(|(from_index, this_index)| {
+                        (
+                            &from.error_enum.error_variants[*from_index],
+                            &self.error_enum.error_variants[*this_index],
+                        )
+                    })
+                    .collect::<Vec<_>>();
+            (&from.error_enum, variant_mappings)
+        })
+    }
+}
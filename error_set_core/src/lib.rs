@@ -0,0 +1,70 @@
+//! The parser, resolver and model behind the `error_set!` macro, for tools that work with error sets without
+//! compiling them, e.g. linters and doc generators. `error_set_impl` generates code from the same model.
+//!
+//! ```rust
+//! use error_set_core::{ast::AstErrorSet, graph::explain, resolve_error_set};
+//!
+//! let error_set: AstErrorSet = syn::parse_str(
+//!     "MediaError := DownloadError || { Parse }
+//!     DownloadError := { IoError(std::io::Error) }",
+//! )?;
+//! let resolved = resolve_error_set(error_set)?;
+//! let media_error = &resolved.graph[0];
+//! assert_eq!(media_error.error_enum.error_name, "MediaError");
+//! assert!(explain(media_error, &resolved.graph).contains("From<DownloadError>"));
+//! # Ok::<(), syn::Error>(())
+//! ```
+
+#![warn(missing_docs)]
+
+/// The syntax of `error_set!` and `error_set_part!`, as parsed
+pub mod ast;
+/// The `From` conversions between resolved error sets
+pub mod graph;
+/// Resolved error sets and their variants
+pub mod model;
+/// Expanding the references between error sets, see [resolve_error_set]
+pub mod resolve;
+// Dev Note: `suggest` and `validate` are only public for `error_set_impl` and `cargo-error-set`, so they are not
+// part of the documented API.
+#[doc(hidden)]
+pub mod suggest;
+#[doc(hidden)]
+pub mod validate;
+
+use ast::{AstErrorKind, AstErrorSet, AstErrorStruct};
+use graph::{ErrorEnumGraphNode, build_graph};
+use resolve::resolve;
+use validate::{validate, validate_display_placeholders, validate_retryable_sources};
+
+/// The error sets of one `error_set!` after resolving
+pub struct ResolvedErrorSet {
+    /// Every error enum, with the `From` conversions between them
+    pub graph: Vec<ErrorEnumGraphNode>,
+    /// Every error struct, these are not resolved
+    pub error_structs: Vec<AstErrorStruct>,
+}
+
+/// Validates and resolves the parsed body of an `error_set!`, the same as the macro does before generating code.
+pub fn resolve_error_set(error_set: AstErrorSet) -> syn::Result<ResolvedErrorSet> {
+    let mut error_enum_decls = Vec::new();
+    let mut error_struct_decls = Vec::new();
+    for item in error_set.set_items.into_iter() {
+        match item {
+            AstErrorKind::Enum(error_enum_decl) => {
+                error_enum_decls.push(error_enum_decl);
+            }
+            AstErrorKind::Struct(struct_decl) => {
+                error_struct_decls.push(struct_decl);
+            }
+        }
+    }
+    validate_display_placeholders(&error_enum_decls, &error_struct_decls)?;
+    validate_retryable_sources(&error_enum_decls)?;
+    let error_enums = resolve(error_enum_decls)?;
+    validate(&error_enums)?;
+    Ok(ResolvedErrorSet {
+        graph: build_graph(error_enums),
+        error_structs: error_struct_decls,
+    })
+}
@@ -0,0 +1,717 @@
+use proc_macro2::TokenStream;
+use syn::{Attribute, Ident, Lit, PathArguments, TypeParam, TypePath, Visibility};
+
+use crate::ast::{
+    AstInlineErrorVariantField, Disabled, DisplayAttribute, DisplayPolicy, LevelAttribute,
+    RetryableAttribute, WITH_DISPLAY_ARGUMENT,
+};
+
+/// Accessors common to every kind of [ErrorVariant]
+pub trait Common {
+    /// Attributes passed through to the generated variant, e.g. docs
+    fn attributes(&self) -> &Vec<Attribute>;
+    /// `#[cfg(..)]` attributes, also applied to the generated code for the variant
+    fn cfg_attributes(&self) -> &Vec<Attribute>;
+    /// `#[display("..")]` - the format string of the variant
+    fn display(&self) -> Option<&DisplayAttribute>;
+    /// The `#[display(..)]` of the set the variant is declared in
+    fn display_policy(&self) -> Option<&DisplayPolicy>;
+    /// `#[debug("..")]` - the format string to use for `Debug`
+    fn debug(&self) -> Option<&DisplayAttribute>;
+    /// `#[retryable]` - whether the variant is retryable
+    fn retryable(&self) -> Option<RetryableAttribute>;
+    /// `#[level(warn)]` - the `tracing` level of the variant
+    fn level(&self) -> Option<LevelAttribute>;
+    /// `#[exit_code(2)]` - the process exit code of the variant
+    fn exit_code(&self) -> Option<u8>;
+    /// `#[io_kind(NotFound)]` - the `std::io::ErrorKind` of the variant
+    fn io_kind(&self) -> Option<&Ident>;
+    /// The name of the variant
+    fn name(&self) -> &Ident;
+    /// The fields, `None` if the variant is not a struct
+    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>>;
+    /// The type of the source, `None` if the variant has none
+    fn source_type(&self) -> Option<&syn::TypePath>;
+}
+
+/// A variant of a resolved error enum
+#[derive(Clone)]
+pub enum ErrorVariant {
+    /// e.g. `ErrorVariantNamed,`
+    Named(Named),
+    /// e.g. `ErrorVariantNamed {...}`
+    Struct(Struct),
+    /// e.g. `ErrorVariantNamed(std::io::Error) {...}`
+    SourceStruct(SourceStruct),
+    /// e.g. `ErrorVariantNamed(std::io::Error)`
+    SourceTuple(SourceTuple),
+}
+
+impl Common for ErrorVariant {
+    fn attributes(&self) -> &Vec<Attribute> {
+        match self {
+            ErrorVariant::Named(e) => e.attributes(),
+            ErrorVariant::Struct(e) => e.attributes(),
+            ErrorVariant::SourceStruct(e) => e.attributes(),
+            ErrorVariant::SourceTuple(e) => e.attributes(),
+        }
+    }
+    fn cfg_attributes(&self) -> &Vec<Attribute> {
+        match self {
+            ErrorVariant::Named(e) => e.cfg_attributes(),
+            ErrorVariant::Struct(e) => e.cfg_attributes(),
+            ErrorVariant::SourceStruct(e) => e.cfg_attributes(),
+            ErrorVariant::SourceTuple(e) => e.cfg_attributes(),
+        }
+    }
+    fn display(&self) -> Option<&DisplayAttribute> {
+        match self {
+            ErrorVariant::Named(e) => e.display(),
+            ErrorVariant::Struct(e) => e.display(),
+            ErrorVariant::SourceStruct(e) => e.display(),
+            ErrorVariant::SourceTuple(e) => e.display(),
+        }
+    }
+    fn display_policy(&self) -> Option<&DisplayPolicy> {
+        match self {
+            ErrorVariant::Named(e) => e.display_policy(),
+            ErrorVariant::Struct(e) => e.display_policy(),
+            ErrorVariant::SourceStruct(e) => e.display_policy(),
+            ErrorVariant::SourceTuple(e) => e.display_policy(),
+        }
+    }
+    fn debug(&self) -> Option<&DisplayAttribute> {
+        match self {
+            ErrorVariant::Named(e) => e.debug(),
+            ErrorVariant::Struct(e) => e.debug(),
+            ErrorVariant::SourceStruct(e) => e.debug(),
+            ErrorVariant::SourceTuple(e) => e.debug(),
+        }
+    }
+    fn retryable(&self) -> Option<RetryableAttribute> {
+        match self {
+            ErrorVariant::Named(e) => e.retryable(),
+            ErrorVariant::Struct(e) => e.retryable(),
+            ErrorVariant::SourceStruct(e) => e.retryable(),
+            ErrorVariant::SourceTuple(e) => e.retryable(),
+        }
+    }
+    fn level(&self) -> Option<LevelAttribute> {
+        match self {
+            ErrorVariant::Named(e) => e.level(),
+            ErrorVariant::Struct(e) => e.level(),
+            ErrorVariant::SourceStruct(e) => e.level(),
+            ErrorVariant::SourceTuple(e) => e.level(),
+        }
+    }
+    fn exit_code(&self) -> Option<u8> {
+        match self {
+            ErrorVariant::Named(e) => e.exit_code(),
+            ErrorVariant::Struct(e) => e.exit_code(),
+            ErrorVariant::SourceStruct(e) => e.exit_code(),
+            ErrorVariant::SourceTuple(e) => e.exit_code(),
+        }
+    }
+    fn io_kind(&self) -> Option<&Ident> {
+        match self {
+            ErrorVariant::Named(e) => e.io_kind(),
+            ErrorVariant::Struct(e) => e.io_kind(),
+            ErrorVariant::SourceStruct(e) => e.io_kind(),
+            ErrorVariant::SourceTuple(e) => e.io_kind(),
+        }
+    }
+    fn name(&self) -> &Ident {
+        match self {
+            ErrorVariant::Named(e) => e.name(),
+            ErrorVariant::Struct(e) => e.name(),
+            ErrorVariant::SourceStruct(e) => e.name(),
+            ErrorVariant::SourceTuple(e) => e.name(),
+        }
+    }
+    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
+        match self {
+            ErrorVariant::Named(e) => e.fields(),
+            ErrorVariant::Struct(e) => e.fields(),
+            ErrorVariant::SourceStruct(e) => e.fields(),
+            ErrorVariant::SourceTuple(e) => e.fields(),
+        }
+    }
+    fn source_type(&self) -> Option<&syn::TypePath> {
+        match self {
+            ErrorVariant::Named(e) => e.source_type(),
+            ErrorVariant::Struct(e) => e.source_type(),
+            ErrorVariant::SourceStruct(e) => e.source_type(),
+            ErrorVariant::SourceTuple(e) => e.source_type(),
+        }
+    }
+}
+
+/// e.g. `Timeout`
+#[derive(Clone)]
+pub struct Named {
+    /// Attributes passed through to the generated variant, e.g. docs
+    pub attributes: Vec<Attribute>,
+    /// `#[cfg(..)]` attributes, also applied to the generated code for the variant
+    pub cfg_attributes: Vec<Attribute>,
+    /// `#[display("..")]` - the format string of the variant
+    pub display: Option<DisplayAttribute>,
+    /// The `#[display(..)]` of the set the variant is declared in
+    pub display_policy: Option<DisplayPolicy>,
+    /// `#[debug("..")]` - the format string to use for `Debug`
+    pub debug: Option<DisplayAttribute>,
+    /// `#[retryable]` - whether the variant is retryable, see [RetryableAttribute]
+    pub retryable: Option<RetryableAttribute>,
+    /// `#[level(warn)]` - the `tracing` level of the variant
+    pub level: Option<LevelAttribute>,
+    /// `#[exit_code(2)]` - the process exit code of the variant
+    pub exit_code: Option<u8>,
+    /// `#[io_kind(NotFound)]` - the `std::io::ErrorKind` of the variant
+    pub io_kind: Option<Ident>,
+    /// The name of the variant
+    pub name: Ident,
+}
+
+impl Common for Named {
+    fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+    fn cfg_attributes(&self) -> &Vec<Attribute> {
+        &self.cfg_attributes
+    }
+    fn display(&self) -> Option<&DisplayAttribute> {
+        self.display.as_ref()
+    }
+    fn display_policy(&self) -> Option<&DisplayPolicy> {
+        self.display_policy.as_ref()
+    }
+    fn debug(&self) -> Option<&DisplayAttribute> {
+        self.debug.as_ref()
+    }
+    fn retryable(&self) -> Option<RetryableAttribute> {
+        self.retryable
+    }
+    fn level(&self) -> Option<LevelAttribute> {
+        self.level
+    }
+    fn exit_code(&self) -> Option<u8> {
+        self.exit_code
+    }
+    fn io_kind(&self) -> Option<&Ident> {
+        self.io_kind.as_ref()
+    }
+    fn name(&self) -> &Ident {
+        &self.name
+    }
+    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
+        None
+    }
+    fn source_type(&self) -> Option<&syn::TypePath> {
+        None
+    }
+}
+
+/// e.g. `Timeout { secs: u64 }`
+#[derive(Clone)]
+pub struct Struct {
+    /// Attributes passed through to the generated variant, e.g. docs
+    pub attributes: Vec<Attribute>,
+    /// `#[cfg(..)]` attributes, also applied to the generated code for the variant
+    pub cfg_attributes: Vec<Attribute>,
+    /// `#[display("..")]` - the format string of the variant
+    pub display: Option<DisplayAttribute>,
+    /// The `#[display(..)]` of the set the variant is declared in
+    pub display_policy: Option<DisplayPolicy>,
+    /// `#[debug("..")]` - the format string to use for `Debug`
+    pub debug: Option<DisplayAttribute>,
+    /// `#[retryable]` - whether the variant is retryable, see [RetryableAttribute]
+    pub retryable: Option<RetryableAttribute>,
+    /// `#[level(warn)]` - the `tracing` level of the variant
+    pub level: Option<LevelAttribute>,
+    /// `#[exit_code(2)]` - the process exit code of the variant
+    pub exit_code: Option<u8>,
+    /// `#[io_kind(NotFound)]` - the `std::io::ErrorKind` of the variant
+    pub io_kind: Option<Ident>,
+    /// The name of the variant
+    pub name: Ident,
+    /// The fields, e.g. `{ secs: u64 }`
+    // Dev Note: This field will never be empty. Otherwise it should just be a [Named]
+    pub fields: Vec<AstInlineErrorVariantField>,
+}
+
+impl Common for Struct {
+    fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+    fn cfg_attributes(&self) -> &Vec<Attribute> {
+        &self.cfg_attributes
+    }
+    fn display(&self) -> Option<&DisplayAttribute> {
+        self.display.as_ref()
+    }
+    fn display_policy(&self) -> Option<&DisplayPolicy> {
+        self.display_policy.as_ref()
+    }
+    fn debug(&self) -> Option<&DisplayAttribute> {
+        self.debug.as_ref()
+    }
+    fn retryable(&self) -> Option<RetryableAttribute> {
+        self.retryable
+    }
+    fn level(&self) -> Option<LevelAttribute> {
+        self.level
+    }
+    fn exit_code(&self) -> Option<u8> {
+        self.exit_code
+    }
+    fn io_kind(&self) -> Option<&Ident> {
+        self.io_kind.as_ref()
+    }
+    fn name(&self) -> &Ident {
+        &self.name
+    }
+    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
+        Some(&self.fields)
+    }
+    fn source_type(&self) -> Option<&syn::TypePath> {
+        None
+    }
+}
+
+/// e.g. `IoError(std::io::Error) { path: PathBuf }`
+#[derive(Clone)]
+pub struct SourceStruct {
+    /// Attributes passed through to the generated variant, e.g. docs
+    pub attributes: Vec<Attribute>,
+    /// `#[cfg(..)]` attributes, also applied to the generated code for the variant
+    pub cfg_attributes: Vec<Attribute>,
+    /// `#[display("..")]` - the format string of the variant
+    pub display: Option<DisplayAttribute>,
+    /// The `#[display(..)]` of the set the variant is declared in
+    pub display_policy: Option<DisplayPolicy>,
+    /// `#[debug("..")]` - the format string to use for `Debug`
+    pub debug: Option<DisplayAttribute>,
+    /// `#[retryable]` - whether the variant is retryable, see [RetryableAttribute]
+    pub retryable: Option<RetryableAttribute>,
+    /// `#[level(warn)]` - the `tracing` level of the variant
+    pub level: Option<LevelAttribute>,
+    /// `#[exit_code(2)]` - the process exit code of the variant
+    pub exit_code: Option<u8>,
+    /// `#[io_kind(NotFound)]` - the `std::io::ErrorKind` of the variant
+    pub io_kind: Option<Ident>,
+    /// `#[display(chain, ..)]` - the display message of the source is appended
+    pub chain: bool,
+    /// The name of the variant
+    pub name: Ident,
+    /// The type of the source, e.g. `std::io::Error` in `IoError(std::io::Error)`
+    pub source_type: syn::TypePath,
+    /// The fields, e.g. `{ path: PathBuf }`
+    // Dev Note: This field can be empty
+    pub fields: Vec<AstInlineErrorVariantField>,
+}
+
+impl Common for SourceStruct {
+    fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+    fn cfg_attributes(&self) -> &Vec<Attribute> {
+        &self.cfg_attributes
+    }
+    fn display(&self) -> Option<&DisplayAttribute> {
+        self.display.as_ref()
+    }
+    fn display_policy(&self) -> Option<&DisplayPolicy> {
+        self.display_policy.as_ref()
+    }
+    fn debug(&self) -> Option<&DisplayAttribute> {
+        self.debug.as_ref()
+    }
+    fn retryable(&self) -> Option<RetryableAttribute> {
+        self.retryable
+    }
+    fn level(&self) -> Option<LevelAttribute> {
+        self.level
+    }
+    fn exit_code(&self) -> Option<u8> {
+        self.exit_code
+    }
+    fn io_kind(&self) -> Option<&Ident> {
+        self.io_kind.as_ref()
+    }
+    fn name(&self) -> &Ident {
+        &self.name
+    }
+    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
+        Some(&self.fields)
+    }
+    fn source_type(&self) -> Option<&syn::TypePath> {
+        Some(&self.source_type)
+    }
+}
+
+/// e.g. `IoError(std::io::Error)`
+#[derive(Clone)]
+pub struct SourceTuple {
+    /// Attributes passed through to the generated variant, e.g. docs
+    pub attributes: Vec<Attribute>,
+    /// `#[cfg(..)]` attributes, also applied to the generated code for the variant
+    pub cfg_attributes: Vec<Attribute>,
+    /// `#[display("..")]` - the format string of the variant
+    pub display: Option<DisplayAttribute>,
+    /// The `#[display(..)]` of the set the variant is declared in
+    pub display_policy: Option<DisplayPolicy>,
+    /// `#[debug("..")]` - the format string to use for `Debug`
+    pub debug: Option<DisplayAttribute>,
+    /// `#[retryable]` - whether the variant is retryable, see [RetryableAttribute]
+    pub retryable: Option<RetryableAttribute>,
+    /// `#[level(warn)]` - the `tracing` level of the variant
+    pub level: Option<LevelAttribute>,
+    /// `#[exit_code(2)]` - the process exit code of the variant
+    pub exit_code: Option<u8>,
+    /// `#[io_kind(NotFound)]` - the `std::io::ErrorKind` of the variant
+    pub io_kind: Option<Ident>,
+    /// `#[display(chain, ..)]` - the display message of the source is appended
+    pub chain: bool,
+    /// The name of the variant
+    pub name: Ident,
+    /// The type of the source, e.g. `std::io::Error` in `IoError(std::io::Error)`
+    pub source_type: syn::TypePath,
+}
+
+impl Common for SourceTuple {
+    fn attributes(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+    fn cfg_attributes(&self) -> &Vec<Attribute> {
+        &self.cfg_attributes
+    }
+    fn display(&self) -> Option<&DisplayAttribute> {
+        self.display.as_ref()
+    }
+    fn display_policy(&self) -> Option<&DisplayPolicy> {
+        self.display_policy.as_ref()
+    }
+    fn debug(&self) -> Option<&DisplayAttribute> {
+        self.debug.as_ref()
+    }
+    fn retryable(&self) -> Option<RetryableAttribute> {
+        self.retryable
+    }
+    fn level(&self) -> Option<LevelAttribute> {
+        self.level
+    }
+    fn exit_code(&self) -> Option<u8> {
+        self.exit_code
+    }
+    fn io_kind(&self) -> Option<&Ident> {
+        self.io_kind.as_ref()
+    }
+    fn name(&self) -> &Ident {
+        &self.name
+    }
+    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
+        None
+    }
+    fn source_type(&self) -> Option<&syn::TypePath> {
+        Some(&self.source_type)
+    }
+}
+
+/// A resolved error set, with the variants of every set it references
+#[derive(Clone)]
+pub struct ErrorEnum {
+    /// Attributes passed through to the generated enum, e.g. docs and derives
+    pub attributes: Vec<Attribute>,
+    /// The visibility of the generated enum
+    pub vis: Visibility,
+    /// The name of the set, e.g. `MediaError`
+    pub error_name: Ident,
+    /// The generic parameters of the set, e.g. `T: Debug` in `ParseError<T: Debug>`
+    pub generics: Vec<TypeParam>,
+    /// `#[skip(..)]` - the trait implementations not generated
+    pub disabled: Disabled,
+    /// `#[strict]` - same-named variants with different shapes in other sets are an error
+    pub strict: bool,
+    /// `#[explain]` - generate an `EXPLAIN` const describing the `From` implementations
+    pub explain: bool,
+    /// `#[debug(display)]` - `Debug` is the variant name followed by the display message
+    pub debug_display: bool,
+    /// The variants of the set and of every set it references, without duplicates
+    pub error_variants: Vec<ErrorVariant>,
+}
+
+impl core::hash::Hash for ErrorEnum {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.error_name.hash(state);
+    }
+}
+
+impl Eq for ErrorEnum {}
+
+impl PartialEq for ErrorEnum {
+    fn eq(&self, other: &Self) -> bool {
+        self.error_name == other.error_name
+    }
+}
+
+//************************************************************************//
+
+/// The value of the tokens if they are a single string literal
+pub fn extract_string_if_str_literal(input: TokenStream) -> Option<String> {
+    if let Ok(Lit::Str(lit)) = syn::parse2::<Lit>(input) {
+        return Some(lit.value());
+    }
+    None
+}
+
+// Dev Note: naive implementation.
+/// If the string contains balanced `{..}` placeholders, so needs format arguments
+pub fn is_format_str(input: &str) -> bool {
+    let mut interpolation_candidate_found = false;
+    let mut last_char = 'a';
+
+    let mut start_count = 0;
+    let mut end_count = 0;
+
+    for c in input.chars() {
+        if c == '{' {
+            if last_char == '{' {
+                last_char = 'a';
+                start_count -= 1;
+                continue;
+            }
+            start_count += 1;
+        } else if c == '}' {
+            if last_char == '}' {
+                last_char = 'a';
+                end_count -= 1;
+                continue;
+            }
+            end_count += 1;
+            if start_count == end_count {
+                interpolation_candidate_found = true;
+            }
+        }
+        last_char = c;
+    }
+    return interpolation_candidate_found && start_count == end_count;
+}
+
+/// The words of a name in lowercase, e.g. `missing book description` for `MissingBookDescription` and `http error`
+/// for `HTTPError`
+pub fn humanize(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (index, &char) in chars.iter().enumerate() {
+        if char == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let previous = index.checked_sub(1).map(|e| chars[e]);
+        let next = chars.get(index + 1);
+        // e.g. `gB` in `MissingBook`, `8E` in `Utf8Error` and `PE` in `HTTPError`
+        let is_word_start = char.is_uppercase()
+            && previous.is_some_and(|e| {
+                e.is_lowercase()
+                    || e.is_ascii_digit()
+                    || (e.is_uppercase() && next.is_some_and(|e| e.is_lowercase()))
+            });
+        if is_word_start && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.extend(char.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words.join(" ")
+}
+
+/// If the display tokens are `opaque`, i.e. `#[display(opaque)]`
+pub fn is_opaque(input: TokenStream) -> bool {
+    if let Ok(ident) = syn::parse2::<Ident>(input) {
+        ident == "opaque"
+    } else {
+        false
+    }
+}
+
+/// The function of the display tokens if they are `with = path::to::fn`, i.e. `#[display(with = my_fmt)]`
+pub fn extract_display_with(input: TokenStream) -> Option<syn::Path> {
+    syn::parse::Parser::parse2(
+        |input: syn::parse::ParseStream| {
+            let ident: Ident = input.parse()?;
+            if ident != WITH_DISPLAY_ARGUMENT {
+                return Err(syn::Error::new(ident.span(), "expected `with`"));
+            }
+            input.parse::<syn::Token![=]>()?;
+            input.parse::<syn::Path>()
+        },
+        input,
+    )
+    .ok()
+}
+
+/// If the display tokens start with `with`, i.e. are intended to be `#[display(with = my_fmt)]`
+pub fn is_display_with(input: TokenStream) -> bool {
+    matches!(input.into_iter().next(), Some(proc_macro2::TokenTree::Ident(ident)) if ident == WITH_DISPLAY_ARGUMENT)
+}
+
+//************************************************************************//
+
+/// e.g. `IoError(std::io::Error)`
+pub fn is_source_tuple_type(error_variant: &ErrorVariant) -> bool {
+    return error_variant.source_type().is_some() && error_variant.fields().is_none();
+}
+
+/// e.g. `IoError(std::io::Error) {}`
+pub fn is_source_only_struct_type(error_variant: &ErrorVariant) -> bool {
+    return error_variant.source_type().is_some()
+        && error_variant
+            .fields()
+            .as_ref()
+            .is_some_and(|e| e.is_empty());
+}
+
+/// e.g. `IoError(std::io::Error) { path: PathBuf }`, also with no fields
+pub fn is_source_struct_type(error_variant: &ErrorVariant) -> bool {
+    return error_variant.source_type().is_some() && error_variant.fields().as_ref().is_some();
+}
+
+/// If the source is `std::io::Error`, e.g. `IoError(std::io::Error)` or `IoError(io::Error)`
+pub fn is_io_error_source(error_variant: &ErrorVariant) -> bool {
+    let Some(source_type) = error_variant.source_type() else {
+        return false;
+    };
+    let mut segments = source_type.path.segments.iter().rev();
+    matches!(
+        (segments.next(), segments.next()),
+        (Some(error), Some(io)) if error.ident == "Error" && io.ident == "io"
+    )
+}
+
+/// To determine if [this] can be converted into [that] without dropping values.
+/// Ignoring backtrace (since this is generated in the `From` impl if missing) and display.
+/// This does not mean [this] is a subset of [that].
+/// Why do they need to be exact?
+/// e.g.
+/// ```ignore
+/// X {
+///   a: String,
+///   b: u32,
+/// }
+/// ```
+/// The above can be converted to the below, by droping the `b`. Even though the below could be considered a "subset".
+/// ```ignore
+/// Y {
+///   a: String
+/// }
+/// ```ignore
+/// If the below was also in the target enum, it would also be valid conversion target
+/// ```ignore
+/// Z {
+///  b: u32
+/// }
+/// ```
+/// Thus, the names and shapes must be exactly the same to avoid this.
+/// Note, there can multiple source tuples or sources only structs with the same wrapped error types (different names).
+/// The first that is encountered becomes the `From` impl of that source error type.
+/// To ensure the correct one is selected, pay attention to `X = A || B` ordering
+/// or define your own `X = { IoError(std::io::Error) } || A || B`
+///
+/// Another example:
+/// ```ignore
+///  N1 {
+///     field: i32
+///  }
+/// ```
+/// ==
+/// ```ignore
+/// N1 {
+///     field: i32
+///  }
+/// ```
+/// !=
+/// ```ignore
+/// N2 {
+///     field: i32
+///  }
+/// ```
+pub fn is_conversion_target(this: &ErrorVariant, that: &ErrorVariant) -> bool {
+    return match (&this.source_type(), &that.source_type()) {
+        (Some(this_source_type), Some(other_source_type)) => {
+            this_source_type.path == other_source_type.path
+                && this.name() == that.name()
+                && this.fields() == that.fields()
+        }
+        (None, None) => this.name() == that.name() && this.fields() == that.fields(),
+        _ => false,
+    };
+}
+
+//************************************************************************//
+
+/// The wrapper and the type inside it, e.g. `std::io::Error` for `Box<std::io::Error>`
+pub fn maybe_extract_known_wrapper_types(ty: &TypePath) -> Option<KnownWrapperTypes<'_>> {
+    let last_part = ty
+        .path
+        .segments
+        .last()
+        .expect("If segments exist there should be more than one.");
+    let wrapper = match &*last_part.ident.to_string() {
+        "Box" => KnownWrapperTypes::Box,
+        _ => return None,
+    };
+    match &last_part.arguments {
+        PathArguments::AngleBracketed(box_args) => {
+            if box_args.args.len() != 1 {
+                return None;
+            }
+            let box_arg = box_args.args.first().unwrap();
+            match box_arg {
+                syn::GenericArgument::Type(box_type) => match box_type {
+                    syn::Type::Path(valid_box_type) => Some(wrapper(valid_box_type)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Source types that wrap another error, for which a `From` of the inner error is also generated
+pub enum KnownWrapperTypes<'a> {
+    /// `Box<T>`, with the inner type `T`
+    Box(&'a TypePath),
+    // TracedError(&'a TypePath),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(
+            humanize("MissingBookDescription"),
+            "missing book description"
+        );
+        assert_eq!(humanize("HTTPError"), "http error");
+        assert_eq!(humanize("Utf8Error"), "utf8 error");
+        assert_eq!(humanize("io_error"), "io error");
+        assert_eq!(humanize("Timeout"), "timeout");
+        assert_eq!(humanize("IO"), "io");
+    }
+
+    #[test]
+    fn test_is_format_str() {
+        assert!(is_format_str("timed out after {secs}s"));
+        assert!(is_format_str("{}"));
+        assert!(!is_format_str("timed out"));
+        assert!(!is_format_str("escaped {{braces}}"));
+        assert!(!is_format_str("unbalanced {"));
+    }
+}
@@ -1,16 +1,18 @@
 use std::collections::HashMap;
 
 use crate::ast::{
-    AstErrorEnumDeclaration, AstErrorVariant, AstInlineErrorVariantField, Disabled, RefError,
+    AstErrorEnumDeclaration, AstErrorVariant, AstInlineErrorVariantField, Disabled, DisplayPolicy,
+    RefError,
 };
-use crate::expand::{ErrorEnum, ErrorVariant, Named, SourceStruct, SourceTuple, Struct};
+use crate::model::{ErrorEnum, ErrorVariant, Named, SourceStruct, SourceTuple, Struct};
+use crate::suggest::with_suggestion;
 
 use quote::ToTokens;
 use syn::{Attribute, Ident, TypeParam, Visibility};
 
 /// Constructs [ErrorEnum]s from the ast, resolving any references to other sets. The returned result is
 /// all error sets with the full expansion.
-pub(crate) fn resolve(
+pub fn resolve(
     error_enum_decls: Vec<AstErrorEnumDeclaration>,
 ) -> syn::Result<Vec<ErrorEnum>> {
     let mut error_enum_builders: Vec<ErrorEnumBuilder> = Vec::new();
@@ -22,11 +24,26 @@ pub(crate) fn resolve(
             error_name,
             generics,
             disabled,
+            strict,
+            explain,
+            display_policy,
+            debug_display,
             parts,
         } = declaration;
 
-        let mut error_enum_builder =
-            ErrorEnumBuilder::new(error_name, attributes, vis, generics, disabled);
+        let mut error_enum_builder = ErrorEnumBuilder::new(
+            error_name,
+            attributes,
+            vis,
+            generics,
+            ErrorEnumOptions {
+                disabled,
+                strict,
+                explain,
+                display_policy,
+                debug_display,
+            },
+        );
 
         for part in parts.into_iter() {
             match part {
@@ -95,9 +112,18 @@ fn resolve_builders_helper<'a>(
             let ref_error_enum_index = match ref_error_enum_index {
                 Some(e) => e,
                 None => {
+                    let ref_name = ref_part.name.to_string();
+                    let declared_names = error_enum_builders
+                        .iter()
+                        .map(|e| e.error_name.to_string())
+                        .collect::<Vec<_>>();
                     return Err(syn::parse::Error::new_spanned(
                         &ref_part.name,
-                        "Not a declared error set.",
+                        with_suggestion(
+                            "Not a declared error set.".to_string(),
+                            &ref_name,
+                            declared_names.iter().map(String::as_str),
+                        ),
                     ));
                 }
             };
@@ -189,6 +215,13 @@ fn resolve_builders_helper<'a>(
                         attributes: error_variant.attributes.clone(),
                         cfg_attributes: error_variant.cfg_attributes.clone(),
                         display: error_variant.display.clone(),
+                        display_policy: error_variant.display_policy.clone(),
+                        chain: error_variant.chain,
+                        debug: error_variant.debug.clone(),
+                        retryable: error_variant.retryable,
+                        level: error_variant.level,
+                        exit_code: error_variant.exit_code,
+                        io_kind: error_variant.io_kind.clone(),
                         name: error_variant.name.clone(),
                         fields: new_fields,
                         source_type: error_variant.source_type.clone(),
@@ -214,7 +247,7 @@ fn resolve_builders_helper<'a>(
 
 /// If the error definitions occupy the same space. Useful since if this space is already occupied e.g. ` X = A || B`
 /// If `A` has a variant like `V1(std::io::Error)` and `B` `V1(std::io::Error)`.
-pub(crate) fn does_occupy_the_same_space(this: &AstErrorVariant, other: &AstErrorVariant) -> bool {
+pub fn does_occupy_the_same_space(this: &AstErrorVariant, other: &AstErrorVariant) -> bool {
     return this.name == other.name;
 }
 
@@ -235,12 +268,21 @@ pub(crate) fn does_occupy_the_same_space(this: &AstErrorVariant, other: &AstErro
 //     }
 // }
 
+/// The options declared by attributes on a set, e.g. `#[strict]` or `#[display(humanize)]`
+struct ErrorEnumOptions {
+    pub disabled: Disabled,
+    pub strict: bool,
+    pub explain: bool,
+    pub display_policy: Option<DisplayPolicy>,
+    pub debug_display: bool,
+}
+
 struct ErrorEnumBuilder {
     pub attributes: Vec<Attribute>,
     pub vis: Visibility,
     pub error_name: Ident,
     pub generics: Vec<TypeParam>,
-    pub disabled: Disabled,
+    pub options: ErrorEnumOptions,
     pub error_variants: Vec<AstErrorVariant>,
     /// Once this is empty, all [ref_parts] have been resolved and [error_variants] is complete.
     pub ref_parts_to_resolve: Vec<RefError>,
@@ -252,14 +294,14 @@ impl ErrorEnumBuilder {
         attributes: Vec<Attribute>,
         vis: Visibility,
         generics: Vec<TypeParam>,
-        disabled: Disabled,
+        options: ErrorEnumOptions,
     ) -> Self {
         Self {
             attributes,
             vis,
             error_name,
             generics,
-            disabled,
+            options,
             error_variants: Vec::new(),
             ref_parts_to_resolve: Vec::new(),
         }
@@ -281,11 +323,23 @@ impl From<ErrorEnumBuilder> for ErrorEnum {
             vis: value.vis,
             error_name: value.error_name,
             generics: value.generics,
-            disabled: value.disabled,
+            disabled: value.options.disabled,
+            strict: value.options.strict,
+            explain: value.options.explain,
+            debug_display: value.options.debug_display,
+            // Dev Note: The set's policy is merged over the policy of the set a variant was declared in
             error_variants: value
                 .error_variants
                 .into_iter()
-                .map(|v| reshape(v))
+                .map(|mut v| {
+                    if let Some(display_policy) = &value.options.display_policy {
+                        v.display_policy = Some(match &v.display_policy {
+                            Some(inherited) => display_policy.merged_with(inherited),
+                            None => display_policy.clone(),
+                        });
+                    }
+                    reshape(v)
+                })
                 .collect::<Vec<_>>(),
         }
     }
@@ -312,6 +366,13 @@ fn reshape(this: AstErrorVariant) -> ErrorVariant {
         attributes,
         cfg_attributes,
         display,
+        display_policy,
+        chain,
+        debug,
+        retryable,
+        level,
+        exit_code,
+        io_kind,
         name,
         fields,
         source_type,
@@ -323,6 +384,13 @@ fn reshape(this: AstErrorVariant) -> ErrorVariant {
                 attributes,
                 cfg_attributes,
                 display,
+                display_policy,
+                debug,
+                retryable,
+                level,
+                exit_code,
+                io_kind,
+                chain,
                 name,
                 source_type,
                 fields,
@@ -334,6 +402,12 @@ fn reshape(this: AstErrorVariant) -> ErrorVariant {
                 attributes,
                 cfg_attributes,
                 display,
+                display_policy,
+                debug,
+                retryable,
+                level,
+                exit_code,
+                io_kind,
                 name,
                 fields,
             });
@@ -344,6 +418,13 @@ fn reshape(this: AstErrorVariant) -> ErrorVariant {
                 attributes,
                 cfg_attributes,
                 display,
+                display_policy,
+                debug,
+                retryable,
+                level,
+                exit_code,
+                io_kind,
+                chain,
                 name,
                 source_type,
             });
@@ -354,6 +435,12 @@ fn reshape(this: AstErrorVariant) -> ErrorVariant {
                 attributes,
                 cfg_attributes,
                 display,
+                display_policy,
+                debug,
+                retryable,
+                level,
+                exit_code,
+                io_kind,
                 name,
             });
         }
@@ -374,6 +461,7 @@ fn replace_generics_in_fields(
             attributes: field.attributes.clone(),
             name: field.name.clone(),
             r#type: new_type.clone(),
+            redact: field.redact,
         };
     }
     // return field.clone();
@@ -388,6 +476,7 @@ fn replace_generics_in_fields(
                 attributes: field.attributes.clone(),
                 name: field.name.clone(),
                 r#type: new_type.clone(),
+                redact: field.redact,
             };
         }
     }
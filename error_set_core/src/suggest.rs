@@ -0,0 +1,113 @@
+use quote::ToTokens;
+
+/// Returns the candidate closest to `target` by edit distance, if any is close enough to plausibly be a typo.
+pub fn closest<'a, I>(target: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = (target.chars().count() / 3).max(1);
+    let target_lower = target.to_lowercase();
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != target)
+        .map(|candidate| {
+            let distance = edit_distance(&target_lower, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Appends a "did you mean" hint to `message` if a close candidate exists.
+pub fn with_suggestion<'a, I>(message: String, target: &str, candidates: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    match closest(target, candidates) {
+        Some(suggestion) => format!("{message} Did you mean `{suggestion}`?"),
+        None => message,
+    }
+}
+
+/// The type path as it would be written by a user, e.g. `std::io::Error` rather than `std :: io :: Error`.
+pub fn type_path_to_string(type_path: &syn::TypePath) -> String {
+    tokens_to_string(type_path)
+}
+
+/// Tokens as they would be written by a user, e.g. `Vec<&'static str>` rather than `Vec < & 'static str >`.
+pub fn tokens_to_string<T: ToTokens>(tokens: &T) -> String {
+    tokens
+        .to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+/// Edit distance between two strings, where insertions, deletions, substitutions and swapping adjacent characters
+/// each count as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j - 1] + substitution_cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("Timeout", "Timeout"), 0);
+        assert_eq!(edit_distance("Timeout", "Timout"), 1);
+        assert_eq!(edit_distance("Timeout", "Tiemout"), 1);
+        assert_eq!(edit_distance("Timeout", "Timeouts"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest() {
+        let candidates = ["DownloadError", "UploadError", "ParseError"];
+        assert_eq!(closest("DownlodError", candidates), Some("DownloadError"));
+        assert_eq!(closest("downloaderror", candidates), Some("DownloadError"));
+        assert_eq!(closest("ConfigError", candidates), None);
+        // Dev Note: The target itself is not a suggestion
+        assert_eq!(closest("ParseError", candidates), None);
+    }
+
+    #[test]
+    fn test_with_suggestion() {
+        assert_eq!(
+            with_suggestion("Unknown `Pars`.".to_string(), "Pars", ["Parse", "IoError"]),
+            "Unknown `Pars`. Did you mean `Parse`?"
+        );
+        assert_eq!(
+            with_suggestion("Unknown `Other`.".to_string(), "Other", ["Parse"]),
+            "Unknown `Other`."
+        );
+    }
+}
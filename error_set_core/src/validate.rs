@@ -0,0 +1,620 @@
+use std::collections::HashSet;
+
+use syn::{Expr, Ident, punctuated::Punctuated, token};
+
+use crate::ast::{
+    AstErrorEnumDeclaration, AstErrorStruct, AstInlineOrRefError, CHAIN_DISPLAY_ARGUMENT,
+    DisplayAttribute, RetryableAttribute, WITH_DISPLAY_ARGUMENT,
+};
+use crate::model::{
+    Common, ErrorEnum, ErrorVariant, extract_display_with, is_conversion_target, is_display_with,
+    is_format_str, is_opaque,
+};
+use crate::suggest::{closest, tokens_to_string, type_path_to_string, with_suggestion};
+
+/// Additional validation logic
+pub fn validate(error_enums: &Vec<ErrorEnum>) -> Result<(), syn::Error> {
+    all_enums_have_unique_names(error_enums)?;
+    unique_variant_names_per_enum(error_enums)?;
+    skipped_froms_exist(error_enums)?;
+    strict_variants_have_consistent_shapes(error_enums)
+}
+
+fn all_enums_have_unique_names(error_enums: &Vec<ErrorEnum>) -> Result<(), syn::Error> {
+    let mut unique_names: HashSet<&Ident> = HashSet::new();
+    for error_enum in error_enums {
+        if unique_names.contains(&error_enum.error_name) {
+            return Err(syn::parse::Error::new_spanned(
+                quote::quote! {error_enum},
+                &format!(
+                    "'{0}' already exists as an error enum.",
+                    error_enum.error_name
+                ),
+            ));
+        }
+        unique_names.insert(&error_enum.error_name);
+    }
+    Ok(())
+}
+
+fn unique_variant_names_per_enum(error_enums: &Vec<ErrorEnum>) -> Result<(), syn::Error> {
+    let mut unique_variant_names: HashSet<&Ident> = HashSet::new();
+    for error_enum in error_enums {
+        for variant in &error_enum.error_variants {
+            let variant_name = &variant.name();
+            if unique_variant_names.contains(variant_name) {
+                return Err(syn::parse::Error::new_spanned(
+                    quote::quote! {variant},
+                    &format!(
+                        "A variant with name '{0}' already exists in error enum '{1}'",
+                        variant_name, error_enum.error_name
+                    ),
+                ));
+            }
+            unique_variant_names.insert(variant_name);
+        }
+        unique_variant_names.clear();
+    }
+    Ok(())
+}
+
+/// Every `#[skip(From(..))]` argument must be a type a `From` would have been generated for - either a set
+/// that can be converted into this set, or the source type of one of this set's variants.
+fn skipped_froms_exist(error_enums: &Vec<ErrorEnum>) -> Result<(), syn::Error> {
+    for error_enum in error_enums {
+        let Some(froms_to_disable) = &error_enum.disabled.from else {
+            continue;
+        };
+        if froms_to_disable.is_empty() {
+            continue;
+        }
+        let convertible_sets = error_enums
+            .iter()
+            .filter(|other| other.error_name != error_enum.error_name)
+            .filter(|other| {
+                other.error_variants.iter().all(|other_variant| {
+                    error_enum
+                        .error_variants
+                        .iter()
+                        .any(|variant| is_conversion_target(other_variant, variant))
+                })
+            })
+            .map(|other| other.error_name.to_string())
+            .collect::<Vec<_>>();
+        let source_types = error_enum
+            .error_variants
+            .iter()
+            .filter_map(|variant| variant.source_type())
+            .collect::<Vec<_>>();
+        for skipped in froms_to_disable {
+            if source_types.contains(&skipped) {
+                continue;
+            }
+            let skipped_name = type_path_to_string(skipped);
+            if convertible_sets.contains(&skipped_name) {
+                continue;
+            }
+            let is_declared_set = error_enums
+                .iter()
+                .any(|other| other.error_name == skipped_name);
+            let message = if is_declared_set {
+                format!(
+                    "`{skipped_name}` is a declared error set, but it cannot be converted into `{0}`, so there is no `From<{skipped_name}>` to skip.",
+                    error_enum.error_name
+                )
+            } else {
+                format!(
+                    "`{skipped_name}` is not a `From` target of `{0}`. Only sets that convert into `{0}` and the source types of its variants can be skipped.",
+                    error_enum.error_name
+                )
+            };
+            let candidates = convertible_sets
+                .iter()
+                .cloned()
+                .chain(source_types.iter().map(|e| type_path_to_string(e)))
+                .collect::<Vec<_>>();
+            return Err(syn::parse::Error::new_spanned(
+                skipped,
+                with_suggestion(
+                    message,
+                    &skipped_name,
+                    candidates.iter().map(String::as_str),
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// For `#[strict]` sets, a variant must have the same shape (fields and source type) as every variant of the same
+/// name in the other sets. Otherwise conversions that look like they should exist are silently not generated.
+fn strict_variants_have_consistent_shapes(error_enums: &Vec<ErrorEnum>) -> Result<(), syn::Error> {
+    let mut errors: Option<syn::Error> = None;
+    for error_enum in error_enums.iter().filter(|e| e.strict) {
+        for other in error_enums {
+            if other.error_name == error_enum.error_name {
+                continue;
+            }
+            for variant in &error_enum.error_variants {
+                let Some(other_variant) = other
+                    .error_variants
+                    .iter()
+                    .find(|e| e.name() == variant.name())
+                else {
+                    continue;
+                };
+                if is_conversion_target(other_variant, variant) {
+                    continue;
+                }
+                let mut dropped = Vec::new();
+                if has_all_variant_names(other, error_enum) {
+                    dropped.push(format!(
+                        "`From<{}> for {}`",
+                        other.error_name, error_enum.error_name
+                    ));
+                }
+                if has_all_variant_names(error_enum, other) {
+                    dropped.push(format!(
+                        "`From<{}> for {}`",
+                        error_enum.error_name, other.error_name
+                    ));
+                }
+                let consequence = if dropped.is_empty() {
+                    "No `From` relations between the two sets depend on it.".to_string()
+                } else {
+                    format!(
+                        "Because of this, {} {} not generated.",
+                        dropped.join(" and "),
+                        if dropped.len() == 1 { "is" } else { "are" }
+                    )
+                };
+                let error = syn::parse::Error::new_spanned(
+                    &error_enum.error_name,
+                    format!(
+                        "`{0}::{1}` and `{2}::{3}` have the same name but different shapes. {consequence}",
+                        error_enum.error_name,
+                        variant_shape(variant),
+                        other.error_name,
+                        variant_shape(other_variant),
+                    ),
+                );
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+/// If every variant name of [this] is also in [that] - ignoring shapes.
+fn has_all_variant_names(this: &ErrorEnum, that: &ErrorEnum) -> bool {
+    this.error_variants.iter().all(|this_variant| {
+        that.error_variants
+            .iter()
+            .any(|that_variant| this_variant.name() == that_variant.name())
+    })
+}
+
+/// e.g. `Timeout(std::io::Error) { secs: u64 }`
+pub fn variant_shape(variant: &ErrorVariant) -> String {
+    let mut shape = variant.name().to_string();
+    if let Some(source_type) = variant.source_type() {
+        shape.push_str(&format!("({})", type_path_to_string(source_type)));
+    }
+    if let Some(fields) = variant.fields() {
+        let fields = fields
+            .iter()
+            .map(|e| format!("{}: {}", e.name, tokens_to_string(&e.r#type)))
+            .collect::<Vec<_>>();
+        if fields.is_empty() {
+            shape.push_str(" {}");
+        } else {
+            shape.push_str(&format!(" {{ {} }}", fields.join(", ")));
+        }
+    }
+    shape
+}
+
+//************************************************************************//
+
+/// Validation of an `error_set_part!` on its own, so mistakes are reported in the part rather than the combined
+/// `error_set!`. References to sets not declared in the part may be declared in another part, so they are left to
+/// rustc, through the imports `error_set_part!` generates.
+pub fn validate_part(
+    error_enum_decls: &[AstErrorEnumDeclaration],
+    error_structs: &[AstErrorStruct],
+) -> Result<(), syn::Error> {
+    let mut unique_names: HashSet<&Ident> = HashSet::new();
+    let names = error_enum_decls
+        .iter()
+        .map(|e| &e.error_name)
+        .chain(error_structs.iter().map(|e| &e.r#struct.ident));
+    for name in names {
+        if !unique_names.insert(name) {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("'{name}' already exists as an error enum."),
+            ));
+        }
+    }
+    for error_enum_decl in error_enum_decls {
+        let mut unique_variant_names: HashSet<&Ident> = HashSet::new();
+        for part in &error_enum_decl.parts {
+            let AstInlineOrRefError::Inline(inline_part) = part else {
+                continue;
+            };
+            for variant in inline_part.error_variants.iter() {
+                if !unique_variant_names.insert(&variant.name) {
+                    return Err(syn::Error::new(
+                        variant.name.span(),
+                        format!(
+                            "A variant with name '{0}' already exists in error enum '{1}'",
+                            variant.name, error_enum_decl.error_name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    validate_display_placeholders(error_enum_decls, error_structs)?;
+    validate_retryable_sources(error_enum_decls)
+}
+
+/// Checks that `#[retryable(source)]` is only on variants with a source. Done before resolving, so each declaration is
+/// only checked once, where it is written.
+pub fn validate_retryable_sources(
+    error_enum_decls: &[AstErrorEnumDeclaration],
+) -> Result<(), syn::Error> {
+    for error_enum_decl in error_enum_decls {
+        for part in &error_enum_decl.parts {
+            let AstInlineOrRefError::Inline(inline_part) = part else {
+                continue;
+            };
+            for variant in inline_part.error_variants.iter() {
+                if variant.retryable == Some(RetryableAttribute::Source)
+                    && variant.source_type.is_none()
+                {
+                    return Err(syn::Error::new_spanned(
+                        &variant.name,
+                        format!(
+                            "`retryable(source)` asks the source if it is retryable, but `{}::{}` has no source.",
+                            error_enum_decl.error_name, variant.name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every placeholder in a `#[display(..)]` or `#[debug(..)]` format string refers to something that is in
+/// scope for the generated `write!` - a field, the source, or an explicit argument. Named placeholders are only
+/// reported when close to one of those, since others may be captured from the scope, e.g. a `const`. Done before
+/// resolving, so each declaration is only checked once, where it is written.
+pub fn validate_display_placeholders(
+    error_enum_decls: &[AstErrorEnumDeclaration],
+    error_structs: &[AstErrorStruct],
+) -> Result<(), syn::Error> {
+    for error_enum_decl in error_enum_decls {
+        for part in &error_enum_decl.parts {
+            let AstInlineOrRefError::Inline(inline_part) = part else {
+                continue;
+            };
+            for variant in inline_part.error_variants.iter() {
+                if variant.chain && variant.source_type.is_none() {
+                    return Err(syn::Error::new_spanned(
+                        &variant.name,
+                        format!(
+                            "`{CHAIN_DISPLAY_ARGUMENT}` appends the display message of the source, but `{}::{}` has no source.",
+                            error_enum_decl.error_name, variant.name
+                        ),
+                    ));
+                }
+                let is_source_tuple = variant.source_type.is_some() && variant.fields.is_none();
+                let mut bindings = variant
+                    .fields
+                    .iter()
+                    .flatten()
+                    .map(|e| e.name.to_string())
+                    .collect::<Vec<_>>();
+                if variant.source_type.is_some() {
+                    bindings.insert(0, "source".to_string());
+                }
+                let error_name = format!("{}::{}", error_enum_decl.error_name, variant.name);
+                if let Some(debug) = &variant.debug {
+                    validate_format(
+                        debug,
+                        "debug",
+                        &error_name,
+                        bindings.clone(),
+                        is_source_tuple,
+                    )?;
+                }
+                let Some(display) = &variant.display else {
+                    continue;
+                };
+                validate_redacted_with(
+                    display,
+                    &error_name,
+                    variant
+                        .fields
+                        .iter()
+                        .flatten()
+                        .filter(|e| e.redact)
+                        .map(|e| &e.name),
+                )?;
+                validate_format(display, "display", &error_name, bindings, is_source_tuple)?;
+            }
+        }
+    }
+    for error_struct in error_structs {
+        let bindings = error_struct
+            .r#struct
+            .fields
+            .iter()
+            .filter_map(|e| e.ident.as_ref())
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        if error_struct.chain && !bindings.iter().any(|e| e == "source") {
+            return Err(syn::Error::new_spanned(
+                &error_struct.r#struct.ident,
+                format!(
+                    "`{CHAIN_DISPLAY_ARGUMENT}` appends the display message of the source, but `{}` has no `source` field.",
+                    error_struct.r#struct.ident
+                ),
+            ));
+        }
+        let Some(display) = &error_struct.display else {
+            continue;
+        };
+        validate_redacted_with(
+            display,
+            &error_struct.r#struct.ident.to_string(),
+            error_struct.redacted_fields.iter(),
+        )?;
+        validate_format(
+            display,
+            "display",
+            &error_struct.r#struct.ident.to_string(),
+            bindings,
+            false,
+        )?;
+    }
+    Ok(())
+}
+
+/// The function of `with = ..` is passed the values of the fields, so it cannot be used with `#[redact]` fields
+fn validate_redacted_with<'a>(
+    display: &DisplayAttribute,
+    error_name: &str,
+    mut redacted_fields: impl Iterator<Item = &'a Ident>,
+) -> Result<(), syn::Error> {
+    if !is_display_with(display.tokens.clone()) {
+        return Ok(());
+    }
+    match redacted_fields.next() {
+        Some(field) => Err(syn::Error::new_spanned(
+            field,
+            format!(
+                "`{field}` of `{error_name}` is `#[redact]`, but the function of `{WITH_DISPLAY_ARGUMENT} = ..` would be passed its value. Use a format string instead."
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// [kind] is the trait the format is for, e.g. `display`. [bindings] are the names bound in the generated match arm.
+/// [is_source_tuple] - a lone format string of a source tuple variant is passed the source as the only positional
+/// argument.
+fn validate_format(
+    display: &DisplayAttribute,
+    kind: &str,
+    error_name: &str,
+    bindings: Vec<String>,
+    is_source_tuple: bool,
+) -> Result<(), syn::Error> {
+    if is_opaque(display.tokens.clone()) {
+        return Ok(());
+    }
+    if is_display_with(display.tokens.clone()) {
+        if extract_display_with(display.tokens.clone()).is_none() {
+            return Err(syn::parse::Error::new_spanned(
+                &display.tokens,
+                format!(
+                    "Expected `{WITH_DISPLAY_ARGUMENT} = path::to::fn` for the {kind} message of `{error_name}`."
+                ),
+            ));
+        }
+        return Ok(());
+    }
+    let Ok(args) = syn::parse::Parser::parse2(
+        Punctuated::<Expr, token::Comma>::parse_terminated,
+        display.tokens.clone(),
+    ) else {
+        return Ok(());
+    };
+    let mut args = args.into_iter();
+    let Some(Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(format_str),
+        ..
+    })) = args.next()
+    else {
+        return Ok(());
+    };
+    let format_str = format_str.value();
+    let mut positional_count = 0;
+    let mut named_args = Vec::new();
+    for arg in args {
+        match arg {
+            Expr::Assign(assign) => match &*assign.left {
+                Expr::Path(path) if path.path.get_ident().is_some() => {
+                    named_args.push(path.path.get_ident().unwrap().to_string());
+                }
+                _ => positional_count += 1,
+            },
+            _ => positional_count += 1,
+        }
+    }
+    let is_lone_format_str = positional_count == 0 && named_args.is_empty();
+    let mut available_names = named_args;
+    if is_lone_format_str {
+        if !is_format_str(&format_str) {
+            // Written as a literal, not interpolated
+            return Ok(());
+        }
+        if is_source_tuple {
+            positional_count = 1;
+        }
+    }
+    if !(is_lone_format_str && is_source_tuple) {
+        available_names.extend(bindings);
+    }
+
+    let available = || {
+        let mut available = (0..positional_count)
+            .map(|i| format!("`{{{i}}}`"))
+            .collect::<Vec<_>>();
+        available.extend(available_names.iter().map(|e| format!("`{{{e}}}`")));
+        if available.is_empty() {
+            "There is nothing available to interpolate.".to_string()
+        } else {
+            format!("Available: {}.", available.join(", "))
+        }
+    };
+    let mut next_implicit_index = 0;
+    for placeholder in format_placeholders(&format_str) {
+        match placeholder {
+            FormatPlaceholder::Positional(index) => {
+                let index = index.unwrap_or_else(|| {
+                    next_implicit_index += 1;
+                    next_implicit_index - 1
+                });
+                if index >= positional_count {
+                    return Err(syn::parse::Error::new_spanned(
+                        &display.tokens,
+                        format!(
+                            "Positional placeholder `{{{index}}}` in the {kind} message of `{error_name}` does not refer to an argument. {}",
+                            available()
+                        ),
+                    ));
+                }
+            }
+            FormatPlaceholder::Named(name) => {
+                if available_names.contains(&name) {
+                    continue;
+                }
+                // Dev Note: Other names may be captured from the scope, e.g. a `const`, which is left to rustc. Only
+                // a name close to a field is likely a typo.
+                if let Some(suggestion) = closest(&name, available_names.iter().map(String::as_str))
+                {
+                    return Err(syn::parse::Error::new_spanned(
+                        &display.tokens,
+                        format!(
+                            "Placeholder `{{{name}}}` in the {kind} message of `{error_name}` does not refer to a field. {} Did you mean `{suggestion}`?",
+                            available()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+enum FormatPlaceholder {
+    /// `None` == `{}`, `Some` == `{0}`
+    Positional(Option<usize>),
+    Named(String),
+}
+
+/// Extracts the arguments referenced by a `format!` style string, including `width$` and `.precision$` arguments
+/// of the format spec.
+fn format_placeholders(input: &str) -> Vec<FormatPlaceholder> {
+    fn argument(arg: &str) -> FormatPlaceholder {
+        if arg.is_empty() {
+            FormatPlaceholder::Positional(None)
+        } else if let Ok(index) = arg.parse::<usize>() {
+            FormatPlaceholder::Positional(Some(index))
+        } else {
+            FormatPlaceholder::Named(arg.to_string())
+        }
+    }
+
+    let mut placeholders = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+        let mut inner = String::new();
+        for c in chars.by_ref() {
+            if c == '}' {
+                break;
+            }
+            inner.push(c);
+        }
+        let (arg, spec) = match inner.split_once(':') {
+            Some((arg, spec)) => (arg.trim(), spec),
+            None => (inner.trim(), ""),
+        };
+        // e.g. `{:.*}` takes the precision as the next positional argument before the value
+        if spec.contains(".*") {
+            placeholders.push(FormatPlaceholder::Positional(None));
+        }
+        placeholders.push(argument(arg));
+        for (index, _) in spec.match_indices('$') {
+            let spec_arg = spec[..index]
+                .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+                .unwrap_or("");
+            if !spec_arg.is_empty() {
+                placeholders.push(argument(spec_arg));
+            }
+        }
+    }
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(input: &str) -> Vec<String> {
+        format_placeholders(input)
+            .into_iter()
+            .map(|e| match e {
+                FormatPlaceholder::Positional(None) => "{}".to_string(),
+                FormatPlaceholder::Positional(Some(index)) => format!("{{{index}}}"),
+                FormatPlaceholder::Named(name) => name,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_format_placeholders() {
+        assert_eq!(placeholders("timed out after {secs}s"), ["secs"]);
+        assert_eq!(
+            placeholders("{} and {0} and { name }"),
+            ["{}", "{0}", "name"]
+        );
+        assert_eq!(placeholders("escaped {{secs}}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_format_placeholders_in_format_spec() {
+        assert_eq!(placeholders("{value:>width$}"), ["value", "width"]);
+        assert_eq!(placeholders("{value:.precision$}"), ["value", "precision"]);
+        assert_eq!(placeholders("{:1$}"), ["{}", "{1}"]);
+        assert_eq!(placeholders("{:.*}"), ["{}", "{}"]);
+    }
+}
@@ -1,61 +1,31 @@
 #![cfg_attr(not(feature = "dev"), allow(dead_code))]
 #![cfg_attr(not(feature = "dev"), allow(unused_variables))]
 
-use std::collections::{HashMap, HashSet};
-
-use proc_macro2::TokenStream;
+use error_set_core::ResolvedErrorSet;
+use error_set_core::ast::{
+    AstErrorStruct, AstInlineErrorVariantField, DisplayTemplatePart, RetryableAttribute,
+    doc_display,
+};
+use error_set_core::graph::{ErrorEnumGraphNode, FromSource, explain, plan_froms};
+use error_set_core::model::{
+    Common, ErrorVariant, extract_display_with, extract_string_if_str_literal, humanize,
+    is_format_str, is_io_error_source, is_opaque, is_source_struct_type, is_source_tuple_type,
+};
+use proc_macro_crate::FoundCrate;
+use proc_macro2::{Span, TokenStream};
 use quote::{TokenStreamExt, quote};
-use syn::{Attribute, Ident, ItemStruct, Lit, PathArguments, TypeParam, TypePath, Visibility};
-
-use crate::ast::{AstErrorStruct, AstInlineErrorVariantField, Disabled, DisplayAttribute};
-
-/// Expand the [ErrorEnum]s into code.
-pub(crate) fn expand(
-    error_enums: Vec<ErrorEnum>,
-    error_structs: Vec<AstErrorStruct>,
-) -> TokenStream {
+use syn::{Ident, ItemStruct, TypeParam};
+
+/// Expand the [ResolvedErrorSet] into code.
+pub(crate) fn expand(resolved_error_set: ResolvedErrorSet) -> TokenStream {
+    let ResolvedErrorSet {
+        graph,
+        error_structs,
+    } = resolved_error_set;
+    let error_set_path = error_set_path();
     let mut token_stream = TokenStream::new();
-    let mut graph: Vec<ErrorEnumGraphNode> = error_enums
-        .into_iter()
-        .map(|e| ErrorEnumGraphNode::new(e))
-        .collect();
-
-    // build a graph of valid conversion `From`'s
-    for building_index in 0..graph.len() {
-        'next_enum: for checking_index in 0..graph.len() {
-            if checking_index == building_index {
-                continue;
-            }
-
-            let mut variant_mappings = Vec::new();
-            'look_for_next_variant_match: for (checking_variant_index, checking_variant) in graph
-                [checking_index]
-                .error_enum
-                .error_variants
-                .iter()
-                .enumerate()
-            {
-                for (building_variant_index, building_variant) in graph[building_index]
-                    .error_enum
-                    .error_variants
-                    .iter()
-                    .enumerate()
-                {
-                    if is_conversion_target(checking_variant, building_variant) {
-                        variant_mappings.push((checking_variant_index, building_variant_index));
-                        continue 'look_for_next_variant_match;
-                    }
-                }
-                continue 'next_enum;
-            }
-            graph[building_index]
-                .froms
-                .push((checking_index, variant_mappings));
-        }
-    }
-
     for error_enum_node in graph.iter() {
-        add_code_for_node(error_enum_node, &*graph, &mut token_stream);
+        add_code_for_node(error_enum_node, &*graph, &error_set_path, &mut token_stream);
     }
     for error_struct in error_structs {
         add_struct_error(error_struct, &mut token_stream);
@@ -63,8 +33,26 @@ pub(crate) fn expand(
     token_stream
 }
 
+/// The path to the `error_set` crate as the user depends on it, e.g. `::errs` for
+/// `errs = { package = "error_set" }`, for generated code that refers to its traits.
+fn error_set_path() -> TokenStream {
+    match proc_macro_crate::crate_name("error_set") {
+        Ok(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, Span::call_site());
+            quote! { ::#name }
+        }
+        // Dev Note: `error_set` never invokes the macro itself, so `Itself` is one of its doctests
+        Ok(FoundCrate::Itself) | Err(_) => quote! { ::error_set },
+    }
+}
+
 fn add_struct_error(error_struct: AstErrorStruct, token_stream: &mut TokenStream) {
-    let AstErrorStruct { r#struct, display } = error_struct;
+    let AstErrorStruct {
+        r#struct,
+        display,
+        chain,
+        redacted_fields,
+    } = error_struct;
     let ItemStruct {
         attrs,
         vis,
@@ -75,12 +63,37 @@ fn add_struct_error(error_struct: AstErrorStruct, token_stream: &mut TokenStream
         semi_token,
     } = &r#struct;
     let (impl_generics, ty_generics, where_generics) = &generics.split_for_impl();
-    let debug = quote! { #[derive(Debug)] };
+    let debug = if redacted_fields.is_empty() {
+        quote! { #[derive(Debug)] }
+    } else {
+        quote! {}
+    };
     token_stream.append_all(quote! {
         #(#attrs)*
         #debug
         #vis #struct_token #struct_name #impl_generics #where_generics #fields #semi_token
     });
+    if !redacted_fields.is_empty() {
+        let struct_name_string = struct_name.to_string();
+        let field_debugs = fields.iter().filter_map(|e| e.ident.as_ref()).map(|name| {
+            let name_string = name.to_string();
+            if redacted_fields.contains(name) {
+                quote! { .field(#name_string, &format_args!("<redacted>")) }
+            } else {
+                quote! { .field(#name_string, &self.#name) }
+            }
+        });
+        let debug_where_generics = debug_where_clause(generics);
+        token_stream.append_all(quote! {
+            impl #impl_generics core::fmt::Debug for #struct_name #ty_generics #debug_where_generics {
+                fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    f.debug_struct(#struct_name_string)
+                        #(#field_debugs)*
+                        .finish()
+                }
+            }
+        });
+    }
 
     let mut has_source_field = false;
     for field in fields.iter() {
@@ -110,15 +123,38 @@ fn add_struct_error(error_struct: AstErrorStruct, token_stream: &mut TokenStream
         });
     }
 
+    // e.g. `failed to read config: permission denied`
+    let chain_write_tokens = if chain {
+        quote::quote! {
+            write!(f, ": {}", self.source)
+        }
+    } else {
+        quote::quote! {
+            Ok(())
+        }
+    };
     if let Some(display) = display {
-        let field_names = fields.iter().filter_map(|e| e.ident.as_ref());
         let tokens = &display.tokens;
+        let field_patterns = fields.iter().filter_map(|e| e.ident.as_ref()).map(|name| {
+            if redacted_fields.contains(name) {
+                quote::quote! { #name: _ }
+            } else {
+                quote::quote! { #name }
+            }
+        });
+        let redacted_bindings = redacted_bindings(redacted_fields.iter());
         let display_write_tokens: TokenStream;
         // e.g. `opaque` (no point in using this here but keeping functionality is consistent with enum variants)
         if is_opaque(tokens.clone()) {
             display_write_tokens = quote::quote! {
                 write!(f, "{}", stringify!(#struct_name))
             };
+        } else if let Some(function) = extract_display_with(tokens.clone()) {
+            // e.g. `with = my_fmt`, called as `my_fmt(field, f)`
+            let field_names = fields.iter().filter_map(|e| e.ident.as_ref());
+            display_write_tokens = quote::quote! {
+                #function(#(#field_names,)* f)
+            };
         } else if let Some(string) = extract_string_if_str_literal(tokens.clone()) {
             // e.g. `"{}"`
             if is_format_str(&string) {
@@ -137,13 +173,23 @@ fn add_struct_error(error_struct: AstErrorStruct, token_stream: &mut TokenStream
                 write!(f, #tokens)
             };
         }
+        // The fields are bound at this point, so the source is written through its binding
+        let display_chain_write_tokens = if chain {
+            quote::quote! {
+                write!(f, ": {}", source)
+            }
+        } else {
+            chain_write_tokens
+        };
         token_stream.append_all(quote! {
             impl #impl_generics core::fmt::Display for #struct_name #ty_generics {
                 #[allow(unused_qualifications)]
                 #[inline]
                 fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                    let #struct_name { #(#field_names),* } = &self;
-                    #display_write_tokens
+                    let #struct_name { #(#field_patterns),* } = &self;
+                    #redacted_bindings
+                    #display_write_tokens?;
+                    #display_chain_write_tokens
                 }
             }
         });
@@ -152,7 +198,8 @@ fn add_struct_error(error_struct: AstErrorStruct, token_stream: &mut TokenStream
             impl #impl_generics core::fmt::Display for #struct_name #ty_generics {
                 #[inline]
                 fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                    write!(f, "{}", stringify!(#struct_name))
+                    write!(f, "{}", stringify!(#struct_name))?;
+                    #chain_write_tokens
                 }
             }
         });
@@ -162,12 +209,19 @@ fn add_struct_error(error_struct: AstErrorStruct, token_stream: &mut TokenStream
 fn add_code_for_node(
     error_enum_node: &ErrorEnumGraphNode,
     graph: &[ErrorEnumGraphNode],
+    error_set_path: &TokenStream,
     token_stream: &mut TokenStream,
 ) {
     add_enum(error_enum_node, token_stream);
     impl_error(error_enum_node, token_stream);
     impl_display(error_enum_node, token_stream);
-    impl_froms(error_enum_node, graph, token_stream);
+    impl_froms(error_enum_node, graph, error_set_path, token_stream);
+    impl_explain(error_enum_node, graph, token_stream);
+    impl_retryable(error_enum_node, error_set_path, token_stream);
+    impl_exit_code(error_enum_node, error_set_path, token_stream);
+    impl_io_error(error_enum_node, token_stream);
+    #[cfg(feature = "tracing")]
+    impl_tracing(error_enum_node, error_set_path, token_stream);
 }
 
 fn add_enum(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenStream) {
@@ -246,7 +300,11 @@ fn add_enum(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenStream
     let attributes = &error_enum.attributes;
     let vis = &error_enum.vis;
     let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
-    let debug = if error_enum.disabled.debug {
+    let has_custom_debug = error_enum.debug_display
+        || error_variants
+            .iter()
+            .any(|e| e.debug().is_some() || e.fields().into_iter().flatten().any(|e| e.redact));
+    let debug = if error_enum.disabled.debug || has_custom_debug {
         quote! {}
     } else {
         quote! { #[derive(Debug)] }
@@ -258,6 +316,161 @@ fn add_enum(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenStream
             #error_variant_tokens
         }
     });
+    if !error_enum.disabled.debug && has_custom_debug {
+        impl_custom_debug(error_enum_node, token_stream);
+    }
+}
+
+/// A `Debug` implementation equivalent to the derived one, except variants with `#[debug("..")]` are written with
+/// it, the rest are written as their name and display message with `#[debug(display)]`, and `#[redact]` fields are
+/// written as `<redacted>`
+fn impl_custom_debug(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenStream) {
+    let error_enum = &error_enum_node.error_enum;
+    let enum_name = &error_enum.error_name;
+    let mut error_variant_tokens = TokenStream::new();
+    for variant in &error_enum.error_variants {
+        let name = variant.name();
+        let name_string = name.to_string();
+        let cfg_attributes = variant.cfg_attributes();
+        let fields = variant.fields().into_iter().flatten().collect::<Vec<_>>();
+        let field_patterns = fields.iter().map(|e| redacted_field_pattern(e));
+        let arm = if let Some(debug) = variant.debug() {
+            // e.g. `#[debug("timed out after {secs}s")]`
+            let tokens = &debug.tokens;
+            let write_tokens = match extract_string_if_str_literal(tokens.clone()) {
+                Some(string) if !is_format_str(&string) => quote::quote! {
+                    write!(f, "{}", #tokens)
+                },
+                Some(_) if is_source_tuple_type(variant) => quote::quote! {
+                    write!(f, #tokens, source)
+                },
+                _ => quote::quote! {
+                    write!(f, #tokens)
+                },
+            };
+            let redacted_bindings =
+                redacted_bindings(fields.iter().filter(|e| e.redact).map(|e| &e.name));
+            let right_side = quote::quote! {
+                {
+                    #redacted_bindings
+                    #write_tokens
+                }
+            };
+            match variant {
+                ErrorVariant::Named(_) => quote::quote! {
+                    #enum_name::#name => #right_side,
+                },
+                ErrorVariant::Struct(_) => quote::quote! {
+                    #enum_name::#name { #(#field_patterns),* } => #right_side,
+                },
+                ErrorVariant::SourceStruct(_) => quote::quote! {
+                    #enum_name::#name { source, #(#field_patterns),* } => #right_side,
+                },
+                ErrorVariant::SourceTuple(_) => quote::quote! {
+                    #enum_name::#name(source) => #right_side,
+                },
+            }
+        } else if error_enum.debug_display {
+            // e.g. `Timeout: timed out after 3s`
+            let right_side = quote::quote! {
+                write!(f, "{}: {}", #name_string, self)
+            };
+            match variant {
+                ErrorVariant::Named(_) => quote::quote! {
+                    #enum_name::#name => #right_side,
+                },
+                ErrorVariant::Struct(_) | ErrorVariant::SourceStruct(_) => quote::quote! {
+                    #enum_name::#name { .. } => #right_side,
+                },
+                ErrorVariant::SourceTuple(_) => quote::quote! {
+                    #enum_name::#name(_) => #right_side,
+                },
+            }
+        } else {
+            let field_debugs = fields.iter().map(|e| {
+                let name = &e.name;
+                let name_string = name.to_string();
+                if e.redact {
+                    quote::quote! { .field(#name_string, &format_args!("<redacted>")) }
+                } else {
+                    quote::quote! { .field(#name_string, #name) }
+                }
+            });
+            match variant {
+                ErrorVariant::Named(_) => quote::quote! {
+                    #enum_name::#name => f.write_str(#name_string),
+                },
+                ErrorVariant::Struct(_) => quote::quote! {
+                    #enum_name::#name { #(#field_patterns),* } => f.debug_struct(#name_string)
+                        #(#field_debugs)*
+                        .finish(),
+                },
+                ErrorVariant::SourceStruct(_) => quote::quote! {
+                    #enum_name::#name { source, #(#field_patterns),* } => f.debug_struct(#name_string)
+                        .field("source", source)
+                        #(#field_debugs)*
+                        .finish(),
+                },
+                ErrorVariant::SourceTuple(_) => quote::quote! {
+                    #enum_name::#name(source) => f.debug_tuple(#name_string).field(source).finish(),
+                },
+            }
+        };
+        error_variant_tokens.append_all(quote::quote! {
+            #(#cfg_attributes)*
+            #arm
+        });
+    }
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
+    let generic_names = error_enum.generics.iter().map(|e| &e.ident);
+    token_stream.append_all(quote::quote! {
+        impl #impl_generics core::fmt::Debug for #enum_name #ty_generics
+        where
+            #(#generic_names: core::fmt::Debug,)*
+        {
+            #[allow(unused_qualifications)]
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                match &*self {
+                    #error_variant_tokens
+                }
+            }
+        }
+    });
+}
+
+/// e.g. `where T: core::fmt::Debug` for each type parameter, like `#[derive(Debug)]`
+fn debug_where_clause(generics: &syn::Generics) -> TokenStream {
+    let predicates = generics
+        .where_clause
+        .iter()
+        .flat_map(|e| e.predicates.iter());
+    let type_params = generics.type_params().map(|e| &e.ident);
+    quote! {
+        where
+            #(#predicates,)*
+            #(#type_params: core::fmt::Debug,)*
+    }
+}
+
+/// e.g. `token: _` for a `#[redact]` field, since its value is not written
+fn redacted_field_pattern(field: &AstInlineErrorVariantField) -> TokenStream {
+    let name = &field.name;
+    if field.redact {
+        quote::quote! { #name: _ }
+    } else {
+        quote::quote! { #name }
+    }
+}
+
+/// Shadows the bindings of `#[redact]` fields, so interpolating them in a display message writes `<redacted>`
+fn redacted_bindings<'a>(field_names: impl Iterator<Item = &'a Ident>) -> TokenStream {
+    let field_names = field_names.collect::<Vec<_>>();
+    quote::quote! {
+        #(
+            #[allow(unused_variables)]
+            let #field_names = format_args!("<redacted>");
+        )*
+    }
 }
 
 fn impl_error(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenStream) {
@@ -330,12 +543,40 @@ fn impl_display(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenSt
     for variant in error_variants {
         let right_side: TokenStream;
         let name = &variant.name();
-        if let Some(display) = &variant.display() {
+        // Dev Note: The policy of the set merged with the policy of the sets the variant was aggregated from
+        let display_policy = variant.display_policy();
+        let chain = match variant {
+            ErrorVariant::SourceStruct(source_struct) => source_struct.chain,
+            ErrorVariant::SourceTuple(source_tuple) => source_tuple.chain,
+            ErrorVariant::Named(_) | ErrorVariant::Struct(_) => false,
+        } || (variant.source_type().is_some()
+            && display_policy.is_some_and(|e| e.chain));
+        let display = match variant.display() {
+            Some(display) => Some(display.clone()),
+            None if display_policy.is_some_and(|e| e.doc) => doc_display(variant.attributes()),
+            None => None,
+        };
+        let default_display = if display_policy.is_some_and(|e| e.humanize) {
+            let humanized = humanize(&name.to_string());
+            quote::quote! {
+                f.write_str(#humanized)
+            }
+        } else {
+            quote::quote! {
+                write!(f, "{}", concat!(stringify!(#enum_name), "::", stringify!(#name)))
+            }
+        };
+        if let Some(display) = &display {
             let tokens = &display.tokens;
             // e.g. `opaque`
             if is_opaque(tokens.clone()) {
+                right_side = default_display;
+            } else if let Some(function) = extract_display_with(tokens.clone()) {
+                // e.g. `with = my_fmt`, called as `my_fmt(source, field, f)`
+                let source = variant.source_type().map(|_| quote::quote! { source, });
+                let field_names = variant.fields().into_iter().flatten().map(|e| &e.name);
                 right_side = quote::quote! {
-                    write!(f, "{}", concat!(stringify!(#enum_name), "::", stringify!(#name)))
+                    #function(#source #(#field_names,)* f)
                 };
             } else if let Some(string) = extract_string_if_str_literal(tokens.clone()) {
                 // e.g. `"{}"`
@@ -362,16 +603,62 @@ fn impl_display(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenSt
                 };
             }
         } else {
-            if is_source_tuple_type(variant) {
+            if is_source_tuple_type(variant) && !chain {
                 right_side = quote::quote! {
                     write!(f, "{}", source)
                 };
             } else {
-                right_side = quote::quote! {
-                    write!(f, "{}", concat!(stringify!(#enum_name), "::", stringify!(#name)))
-                };
+                right_side = default_display;
             }
         }
+        // e.g. `failed to read config: permission denied`
+        let right_side = if chain {
+            quote::quote! {
+                {
+                    #right_side?;
+                    write!(f, ": {}", source)
+                }
+            }
+        } else {
+            right_side
+        };
+        let redacted_field_names = variant
+            .fields()
+            .into_iter()
+            .flatten()
+            .filter(|e| e.redact)
+            .map(|e| &e.name);
+        let redacted_bindings = redacted_bindings(redacted_field_names);
+        let right_side = if redacted_bindings.is_empty() {
+            right_side
+        } else {
+            quote::quote! {
+                {
+                    #redacted_bindings
+                    #right_side
+                }
+            }
+        };
+        // e.g. `[{set}] {message}`
+        let right_side = match display_policy.and_then(|e| e.template.as_ref()) {
+            Some(template) => {
+                let enum_name_string = enum_name.to_string();
+                let writes = template.iter().map(|part| match part {
+                    DisplayTemplatePart::Literal(literal) => {
+                        quote::quote! { f.write_str(#literal)?; }
+                    }
+                    DisplayTemplatePart::Set => quote::quote! { f.write_str(#enum_name_string)?; },
+                    DisplayTemplatePart::Message => quote::quote! { #right_side?; },
+                });
+                quote::quote! {
+                    {
+                        #(#writes)*
+                        Ok(())
+                    }
+                }
+            }
+            None => right_side,
+        };
 
         match variant {
             ErrorVariant::Named(named) => {
@@ -383,18 +670,18 @@ fn impl_display(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenSt
             }
             ErrorVariant::Struct(r#struct) => {
                 let cfg_attributes = &r#struct.cfg_attributes;
-                let field_names = r#struct.fields.iter().map(|e| &e.name);
+                let field_patterns = r#struct.fields.iter().map(redacted_field_pattern);
                 error_variant_tokens.append_all(quote::quote! {
                     #(#cfg_attributes)*
-                    #enum_name::#name { #(#field_names),*  } =>  #right_side,
+                    #enum_name::#name { #(#field_patterns),*  } =>  #right_side,
                 });
             }
             ErrorVariant::SourceStruct(source_struct) => {
                 let cfg_attributes = &source_struct.cfg_attributes;
-                let field_names = source_struct.fields.iter().map(|e| &e.name);
+                let field_patterns = source_struct.fields.iter().map(redacted_field_pattern);
                 error_variant_tokens.append_all(quote::quote! {
                     #(#cfg_attributes)*
-                    #enum_name::#name { source, #(#field_names),* } =>  #right_side,
+                    #enum_name::#name { source, #(#field_patterns),* } =>  #right_side,
                 });
             }
             ErrorVariant::SourceTuple(source_tuple) => {
@@ -422,225 +709,523 @@ fn impl_display(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenSt
 fn impl_froms(
     error_enum_node: &ErrorEnumGraphNode,
     graph: &[ErrorEnumGraphNode],
+    error_set_path: &TokenStream,
     token_stream: &mut TokenStream,
 ) {
     let error_enum = &error_enum_node.error_enum;
-    let from = &error_enum.disabled.from;
-    if from.as_ref().is_some_and(|e| e.is_empty()) {
-        return;
-    }
-    let temp = Vec::new();
-    let froms_to_disable = from.as_ref().unwrap_or(&temp);
-    let froms_to_disable_idents = froms_to_disable
-        .iter()
-        .flat_map(|e| e.path.get_ident())
-        .collect::<Vec<_>>();
     let error_enum_name = &error_enum.error_name;
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
 
-    for (from_error_enum, variant_mappings) in error_enum_node.resolved_froms(graph) {
-        if froms_to_disable_idents.contains(&&from_error_enum.error_name) {
+    for from_plan in plan_froms(error_enum_node, graph) {
+        if from_plan.suppressed.is_some() {
             continue;
         }
-        let mut all_cfg_attributes = HashSet::new();
-        let mut error_branch_tokens = TokenStream::new();
-        let from_error_enum_name = &from_error_enum.error_name;
-        for (from_error_enum_variant, error_enum_variant) in variant_mappings {
-            #[cfg(feature = "dev")]
-            {
-                assert!(
-                    from_error_enum
-                        .error_variants
-                        .iter()
-                        .any(|e| e.name() == from_error_enum_variant.name()),
-                    "Variant not found in from error enum"
-                );
-                assert!(
-                    error_enum
-                        .error_variants
-                        .iter()
-                        .any(|e| e.name() == error_enum_variant.name()),
-                    "Variant not found in error enum"
-                );
-                let from = from_error_enum_variant.name();
-                let to = error_enum_variant.name();
-                assert!(
-                    is_conversion_target(from_error_enum_variant, error_enum_variant),
-                    "Not a valid conversion target\n\nfrom:\n\n{from}\n\nto:\n\n{to}"
-                );
-            }
-            all_cfg_attributes.extend(from_error_enum_variant.cfg_attributes().clone());
-            all_cfg_attributes.extend(error_enum_variant.cfg_attributes().clone());
-            let arm: Option<TokenStream> = match (from_error_enum_variant, error_enum_variant) {
-                (ErrorVariant::Named(this), ErrorVariant::Named(that)) => Some(name_to_name(
-                    from_error_enum_name,
-                    &this.name,
-                    error_enum_name,
-                    &that.name,
-                )),
-                (ErrorVariant::Named(this), ErrorVariant::Struct(that)) => None,
-                (ErrorVariant::Named(this), ErrorVariant::SourceStruct(that)) => None,
-                (ErrorVariant::Named(this), ErrorVariant::SourceTuple(that)) => None,
-                (ErrorVariant::Struct(this), ErrorVariant::Named(that)) => None,
-                (ErrorVariant::Struct(this), ErrorVariant::Struct(that)) => Some(struct_to_struct(
-                    from_error_enum_name,
-                    &this.name,
-                    &this.fields,
-                    error_enum_name,
-                    &that.name,
-                    &that.fields,
-                )),
-                (ErrorVariant::Struct(this), ErrorVariant::SourceStruct(that)) => None,
-                (ErrorVariant::Struct(this), ErrorVariant::SourceTuple(that)) => None,
-                (ErrorVariant::SourceStruct(this), ErrorVariant::Named(that)) => None,
-                (ErrorVariant::SourceStruct(this), ErrorVariant::Struct(that)) => None,
-                (ErrorVariant::SourceStruct(this), ErrorVariant::SourceStruct(that)) => {
-                    Some(source_struct_to_source_struct(
-                        from_error_enum_name,
-                        &this.name,
-                        &this.fields,
-                        error_enum_name,
-                        &that.name,
-                        &that.fields,
-                    ))
-                }
-                (ErrorVariant::SourceStruct(this), ErrorVariant::SourceTuple(that)) => {
-                    Some(source_struct_to_source_tuple(
-                        from_error_enum_name,
-                        &this.name,
-                        &this.fields,
-                        error_enum_name,
-                        &that.name,
-                    ))
-                }
-                (ErrorVariant::SourceTuple(this), ErrorVariant::Named(that)) => None,
-                (ErrorVariant::SourceTuple(this), ErrorVariant::Struct(that)) => None,
-                (ErrorVariant::SourceTuple(this), ErrorVariant::SourceStruct(that)) => {
-                    if that.fields.is_empty() {
-                        Some(source_tuple_to_source_only_struct(
-                            from_error_enum_name,
-                            &this.name,
-                            error_enum_name,
-                            &that.name,
-                        ))
-                    } else {
-                        None
+        match from_plan.from {
+            FromSource::Set {
+                error_enum: from_error_enum,
+                variant_mappings,
+            } => {
+                let from_error_enum_name = &from_error_enum.error_name;
+                let mut error_branch_tokens = TokenStream::new();
+                for (from_error_enum_variant, error_enum_variant) in variant_mappings {
+                    #[cfg(feature = "dev")]
+                    {
+                        assert!(
+                            from_error_enum
+                                .error_variants
+                                .iter()
+                                .any(|e| e.name() == from_error_enum_variant.name()),
+                            "Variant not found in from error enum"
+                        );
+                        assert!(
+                            error_enum
+                                .error_variants
+                                .iter()
+                                .any(|e| e.name() == error_enum_variant.name()),
+                            "Variant not found in error enum"
+                        );
+                        let from = from_error_enum_variant.name();
+                        let to = error_enum_variant.name();
+                        assert!(
+                            error_set_core::model::is_conversion_target(
+                                from_error_enum_variant,
+                                error_enum_variant
+                            ),
+                            "Not a valid conversion target\n\nfrom:\n\n{from}\n\nto:\n\n{to}"
+                        );
+                    }
+                    let arm: Option<TokenStream> =
+                        match (from_error_enum_variant, error_enum_variant) {
+                            (ErrorVariant::Named(this), ErrorVariant::Named(that)) => {
+                                Some(name_to_name(
+                                    from_error_enum_name,
+                                    &this.name,
+                                    error_enum_name,
+                                    &that.name,
+                                ))
+                            }
+                            (ErrorVariant::Named(this), ErrorVariant::Struct(that)) => None,
+                            (ErrorVariant::Named(this), ErrorVariant::SourceStruct(that)) => None,
+                            (ErrorVariant::Named(this), ErrorVariant::SourceTuple(that)) => None,
+                            (ErrorVariant::Struct(this), ErrorVariant::Named(that)) => None,
+                            (ErrorVariant::Struct(this), ErrorVariant::Struct(that)) => {
+                                Some(struct_to_struct(
+                                    from_error_enum_name,
+                                    &this.name,
+                                    &this.fields,
+                                    error_enum_name,
+                                    &that.name,
+                                    &that.fields,
+                                ))
+                            }
+                            (ErrorVariant::Struct(this), ErrorVariant::SourceStruct(that)) => None,
+                            (ErrorVariant::Struct(this), ErrorVariant::SourceTuple(that)) => None,
+                            (ErrorVariant::SourceStruct(this), ErrorVariant::Named(that)) => None,
+                            (ErrorVariant::SourceStruct(this), ErrorVariant::Struct(that)) => None,
+                            (
+                                ErrorVariant::SourceStruct(this),
+                                ErrorVariant::SourceStruct(that),
+                            ) => Some(source_struct_to_source_struct(
+                                from_error_enum_name,
+                                &this.name,
+                                &this.fields,
+                                error_enum_name,
+                                &that.name,
+                                &that.fields,
+                            )),
+                            (ErrorVariant::SourceStruct(this), ErrorVariant::SourceTuple(that)) => {
+                                Some(source_struct_to_source_tuple(
+                                    from_error_enum_name,
+                                    &this.name,
+                                    &this.fields,
+                                    error_enum_name,
+                                    &that.name,
+                                ))
+                            }
+                            (ErrorVariant::SourceTuple(this), ErrorVariant::Named(that)) => None,
+                            (ErrorVariant::SourceTuple(this), ErrorVariant::Struct(that)) => None,
+                            (ErrorVariant::SourceTuple(this), ErrorVariant::SourceStruct(that)) => {
+                                if that.fields.is_empty() {
+                                    Some(source_tuple_to_source_only_struct(
+                                        from_error_enum_name,
+                                        &this.name,
+                                        error_enum_name,
+                                        &that.name,
+                                    ))
+                                } else {
+                                    None
+                                }
+                            }
+                            (ErrorVariant::SourceTuple(this), ErrorVariant::SourceTuple(that)) => {
+                                Some(source_tuple_to_source_tuple(
+                                    from_error_enum_name,
+                                    &this.name,
+                                    error_enum_name,
+                                    &that.name,
+                                ))
+                            }
+                        };
+                    if let Some(arm) = arm {
+                        error_branch_tokens.append_all(arm);
                     }
                 }
-                (ErrorVariant::SourceTuple(this), ErrorVariant::SourceTuple(that)) => {
-                    Some(source_tuple_to_source_tuple(
-                        from_error_enum_name,
-                        &this.name,
-                        error_enum_name,
-                        &that.name,
-                    ))
-                }
-            };
-            if let Some(arm) = arm {
-                error_branch_tokens.append_all(arm);
+                let (from_impl_generics, from_ty_generics) =
+                    generic_tokens(&from_error_enum.generics);
+                let all_cfg_attributes = from_plan.cfg_attributes.iter();
+                let trace_tokens =
+                    trace_conversion(from_error_enum_name, error_enum_name, error_set_path);
+                token_stream.append_all(quote::quote! {
+                    #(#all_cfg_attributes)*
+                    impl #impl_generics From<#from_error_enum_name #from_ty_generics> for #error_enum_name #ty_generics {
+                        fn from(error: #from_error_enum_name #from_ty_generics) -> Self {
+                            #trace_tokens
+                            match error {
+                                #error_branch_tokens
+                            }
+                        }
+                    }
+                });
+            }
+            FromSource::SourceType {
+                variant: error_variant,
+                source_type,
+            } => {
+                let variant_name = &error_variant.name();
+                let cfg_attributes = &from_plan.cfg_attributes;
+                let construct_variant = if is_source_tuple_type(error_variant) {
+                    quote::quote! { #error_enum_name::#variant_name(error) }
+                } else {
+                    quote::quote! { #error_enum_name::#variant_name { source: error } }
+                };
+                token_stream.append_all(quote::quote! {
+                    #(#cfg_attributes)*
+                    impl #impl_generics From<#source_type> for #error_enum_name #ty_generics {
+                        fn from(error: #source_type) -> Self {
+                            #construct_variant
+                        }
+                    }
+                });
+            }
+            FromSource::BoxedSourceType {
+                variant: error_variant,
+                inner_type,
+            } => {
+                let variant_name = &error_variant.name();
+                let cfg_attributes = &from_plan.cfg_attributes;
+                let construct_variant = if is_source_tuple_type(error_variant) {
+                    quote::quote! { #error_enum_name::#variant_name(Box::new(error)) }
+                } else {
+                    quote::quote! { #error_enum_name::#variant_name { source: Box::new(error) } }
+                };
+                token_stream.append_all(quote::quote! {
+                    #(#cfg_attributes)*
+                    impl #impl_generics From<#inner_type> for #error_enum_name #ty_generics {
+                        fn from(error: #inner_type) -> Self {
+                            #construct_variant
+                        }
+                    }
+                });
             }
         }
-        // Dev Note: If from has generics and they are not the same as target's, then there is no guarantee that `impl_generics`
-        // will contain all of and the correct generics definitions that are for `from_ty_generics`. Merging may cause
-        // conflicts. This guard likely won't ever be removed since the correct mixture of generics may be
-        // impossible to determine without the user explicitly specifying. Even if this guard does not hold,
-        // an "unwanted" (but no compile error) `From` may be generated. This is an edge case and we are
-        // being optimistic, so we don't just not implement `From` for all generics. But a user can opt-out
-        // with `#[disable(From(..))]`
-        if !from_error_enum.generics.is_empty() && error_enum.generics != from_error_enum.generics {
-            continue;
+    }
+}
+
+/// Generates an `EXPLAIN` const with the [explain] of the error enum.
+fn impl_explain(
+    error_enum_node: &ErrorEnumGraphNode,
+    graph: &[ErrorEnumGraphNode],
+    token_stream: &mut TokenStream,
+) {
+    let error_enum = &error_enum_node.error_enum;
+    if !error_enum.explain {
+        return;
+    }
+    let error_enum_name = &error_enum.error_name;
+    let explanation = explain(error_enum_node, graph);
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
+    token_stream.append_all(quote::quote! {
+        impl #impl_generics #error_enum_name #ty_generics {
+            /// Which `From` implementations were generated for this error set and which were not, and why.
+            pub const EXPLAIN: &'static str = #explanation;
         }
-        let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
-        let (from_impl_generics, from_ty_generics) = generic_tokens(&from_error_enum.generics);
-        let all_cfg_attributes = all_cfg_attributes.iter();
-        token_stream.append_all(quote::quote! {
-            #(#all_cfg_attributes)*
-            impl #impl_generics From<#from_error_enum_name #from_ty_generics> for #error_enum_name #ty_generics {
-                fn from(error: #from_error_enum_name #from_ty_generics) -> Self {
-                    match error {
-                        #error_branch_tokens
-                    }
+    });
+}
+
+/// Generates `is_retryable` from the variants marked `#[retryable]` or `#[retryable(source)]`, and implements
+/// `error_set::Retryable` with it so sets wrapping this one can ask it. Sets without markers are never retryable.
+fn impl_retryable(
+    error_enum_node: &ErrorEnumGraphNode,
+    error_set_path: &TokenStream,
+    token_stream: &mut TokenStream,
+) {
+    let error_enum = &error_enum_node.error_enum;
+    let error_enum_name = &error_enum.error_name;
+    let mut retryable_branches = TokenStream::new();
+    for variant in &error_enum.error_variants {
+        let Some(retryable) = variant.retryable() else {
+            continue;
+        };
+        let name = variant.name();
+        let cfg_attributes = variant.cfg_attributes();
+        let right_side = match retryable {
+            RetryableAttribute::Always => quote::quote! { true },
+            RetryableAttribute::Source => quote::quote! {
+                #error_set_path::Retryable::is_retryable(source)
+            },
+        };
+        let pattern = match (variant, retryable) {
+            (ErrorVariant::Named(_), _) => quote::quote! { #error_enum_name::#name },
+            (ErrorVariant::Struct(_), _)
+            | (ErrorVariant::SourceStruct(_), RetryableAttribute::Always) => {
+                quote::quote! { #error_enum_name::#name { .. } }
+            }
+            (ErrorVariant::SourceStruct(_), RetryableAttribute::Source) => {
+                quote::quote! { #error_enum_name::#name { source, .. } }
+            }
+            (ErrorVariant::SourceTuple(_), RetryableAttribute::Always) => {
+                quote::quote! { #error_enum_name::#name(_) }
+            }
+            (ErrorVariant::SourceTuple(_), RetryableAttribute::Source) => {
+                quote::quote! { #error_enum_name::#name(source) }
+            }
+        };
+        retryable_branches.append_all(quote::quote! {
+            #(#cfg_attributes)*
+            #pattern => #right_side,
+        });
+    }
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
+    token_stream.append_all(quote::quote! {
+        impl #impl_generics #error_enum_name #ty_generics {
+            /// If the error is transient, so the operation that caused it may succeed if retried.
+            pub fn is_retryable(&self) -> bool {
+                match self {
+                    #retryable_branches
+                    #[allow(unreachable_patterns)]
+                    _ => false,
                 }
             }
+        }
+
+        impl #impl_generics #error_set_path::Retryable for #error_enum_name #ty_generics {
+            #[inline]
+            fn is_retryable(&self) -> bool {
+                #error_enum_name::is_retryable(self)
+            }
+        }
+    });
+}
+
+/// Generates `exit_code` from the variants' `#[exit_code(..)]`, defaulting to `1`, and implements
+/// `error_set::ToExitCode` with it, so the set can be returned from `main` through `error_set::Exit`. Nothing is
+/// generated for sets without exit codes.
+fn impl_exit_code(
+    error_enum_node: &ErrorEnumGraphNode,
+    error_set_path: &TokenStream,
+    token_stream: &mut TokenStream,
+) {
+    let error_enum = &error_enum_node.error_enum;
+    if error_enum
+        .error_variants
+        .iter()
+        .all(|e| e.exit_code().is_none())
+    {
+        return;
+    }
+    let error_enum_name = &error_enum.error_name;
+    let mut exit_code_branches = TokenStream::new();
+    for variant in &error_enum.error_variants {
+        let Some(exit_code) = variant.exit_code() else {
+            continue;
+        };
+        let name = variant.name();
+        let cfg_attributes = variant.cfg_attributes();
+        let pattern = match variant {
+            ErrorVariant::Named(_) => quote::quote! { #error_enum_name::#name },
+            ErrorVariant::Struct(_) | ErrorVariant::SourceStruct(_) => {
+                quote::quote! { #error_enum_name::#name { .. } }
+            }
+            ErrorVariant::SourceTuple(_) => quote::quote! { #error_enum_name::#name(_) },
+        };
+        exit_code_branches.append_all(quote::quote! {
+            #(#cfg_attributes)*
+            #pattern => #exit_code,
         });
     }
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
+    token_stream.append_all(quote::quote! {
+        impl #impl_generics #error_enum_name #ty_generics {
+            /// The process exit code of the variant, from `#[exit_code(..)]`, or `1` if it has none.
+            pub fn exit_code(&self) -> u8 {
+                match self {
+                    #exit_code_branches
+                    #[allow(unreachable_patterns)]
+                    _ => 1,
+                }
+            }
+        }
 
-    // Do not impl `From` for source where source is the same between multiple variants
-    let mut source_type_to_error_variants = HashMap::new();
-    let mut all_source_types = HashSet::new();
-    for error_variant in error_enum.error_variants.iter() {
-        if let Some(source_type) = error_variant.source_type() {
-            if froms_to_disable.contains(source_type) {
-                continue;
+        impl #impl_generics #error_set_path::ToExitCode for #error_enum_name #ty_generics {
+            #[inline]
+            fn exit_code(&self) -> u8 {
+                #error_enum_name::exit_code(self)
             }
-            if all_source_types.contains(source_type) {
-                source_type_to_error_variants.remove(source_type);
-            } else {
-                all_source_types.insert(source_type);
-                source_type_to_error_variants.insert(source_type, error_variant);
+        }
+    });
+}
+
+/// Generates `From<Set> for std::io::Error` if any variant has an `#[io_kind(..)]`. Sources that are `std::io::Error` are
+/// returned as is, and other variants are wrapped with their kind, or `Other`, so the set can be recovered with
+/// `get_ref` and `downcast_ref`.
+fn impl_io_error(error_enum_node: &ErrorEnumGraphNode, token_stream: &mut TokenStream) {
+    let error_enum = &error_enum_node.error_enum;
+    if error_enum
+        .error_variants
+        .iter()
+        .all(|e| e.io_kind().is_none())
+    {
+        return;
+    }
+    let error_enum_name = &error_enum.error_name;
+    let mut io_error_branches = TokenStream::new();
+    for variant in &error_enum.error_variants {
+        let name = variant.name();
+        let cfg_attributes = variant.cfg_attributes();
+        let branch = if is_io_error_source(variant) {
+            match variant {
+                ErrorVariant::SourceStruct(_) => quote::quote! {
+                    #error_enum_name::#name { source, .. } => source,
+                },
+                _ => quote::quote! {
+                    #error_enum_name::#name(source) => source,
+                },
+            }
+        } else {
+            let io_kind = match variant.io_kind() {
+                Some(io_kind) => quote::quote! { #io_kind },
+                None => quote::quote! { Other },
+            };
+            let pattern = match variant {
+                ErrorVariant::Named(_) => quote::quote! { #error_enum_name::#name },
+                ErrorVariant::Struct(_) | ErrorVariant::SourceStruct(_) => {
+                    quote::quote! { #error_enum_name::#name { .. } }
+                }
+                ErrorVariant::SourceTuple(_) => quote::quote! { #error_enum_name::#name(_) },
+            };
+            quote::quote! {
+                #pattern => ::std::io::Error::new(::std::io::ErrorKind::#io_kind, error),
             }
+        };
+        io_error_branches.append_all(quote::quote! {
+            #(#cfg_attributes)*
+            #branch
+        });
+    }
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
+    token_stream.append_all(quote::quote! {
+        impl #impl_generics From<#error_enum_name #ty_generics> for ::std::io::Error
+        where
+            #error_enum_name #ty_generics: ::std::error::Error + Send + Sync + 'static,
+        {
+            fn from(error: #error_enum_name #ty_generics) -> Self {
+                match error {
+                    #io_error_branches
+                }
+            }
+        }
+    });
+}
+
+/// A `TRACE` event for a conversion from one set into another, when the `tracing` feature is enabled.
+fn trace_conversion(
+    from_error_enum_name: &Ident,
+    error_enum_name: &Ident,
+    error_set_path: &TokenStream,
+) -> TokenStream {
+    #[cfg(feature = "tracing")]
+    {
+        let from = from_error_enum_name.to_string();
+        let to = error_enum_name.to_string();
+        quote::quote! {
+            #error_set_path::tracing::trace!(from = #from, to = #to, "error converted");
         }
     }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (from_error_enum_name, error_enum_name, error_set_path);
+        TokenStream::new()
+    }
+}
 
-    // Add `From`'s for all valid variants that are wrappers around source errors.
-    for error_variant in source_type_to_error_variants.values() {
-        let source_type = error_variant.source_type();
-        let inner_source_type_if_concrete_box =
-            source_type.and_then(|e| maybe_extract_known_wrapper_types(e));
-        if is_source_tuple_type(error_variant) {
-            let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
-            let variant_name = &error_variant.name();
-            let cfg_attributes = &error_variant.cfg_attributes();
-            token_stream.append_all(quote::quote! {
-                #(#cfg_attributes)*
-                impl #impl_generics From<#source_type> for #error_enum_name #ty_generics {
-                    fn from(error: #source_type) -> Self {
-                        #error_enum_name::#variant_name(error)
-                    }
+/// Generates `level` from the variants' `#[level(..)]`, defaulting to `ERROR`, and `trace`, which emits an event at
+/// that level with the variant's code and fields. Only sets with a `#[level(..)]` variant get these, so they do not
+/// clash with methods of the same name on other sets.
+#[cfg(feature = "tracing")]
+fn impl_tracing(
+    error_enum_node: &ErrorEnumGraphNode,
+    error_set_path: &TokenStream,
+    token_stream: &mut TokenStream,
+) {
+    use error_set_core::ast::LevelAttribute;
+
+    let error_enum = &error_enum_node.error_enum;
+    if error_enum
+        .error_variants
+        .iter()
+        .all(|e| e.level().is_none())
+    {
+        return;
+    }
+    let error_enum_name = &error_enum.error_name;
+    let mut level_branches = TokenStream::new();
+    let mut trace_branches = TokenStream::new();
+    for variant in &error_enum.error_variants {
+        let name = variant.name();
+        let code = format!("{}::{}", error_enum_name, name);
+        let cfg_attributes = variant.cfg_attributes();
+        let level = match variant.level().unwrap_or(LevelAttribute::Error) {
+            LevelAttribute::Trace => quote::quote! { #error_set_path::tracing::Level::TRACE },
+            LevelAttribute::Debug => quote::quote! { #error_set_path::tracing::Level::DEBUG },
+            LevelAttribute::Info => quote::quote! { #error_set_path::tracing::Level::INFO },
+            LevelAttribute::Warn => quote::quote! { #error_set_path::tracing::Level::WARN },
+            LevelAttribute::Error => quote::quote! { #error_set_path::tracing::Level::ERROR },
+        };
+        let fields = variant.fields().into_iter().flatten().collect::<Vec<_>>();
+        let field_patterns = fields.iter().map(|e| redacted_field_pattern(e));
+        let field_values = fields.iter().map(|e| {
+            let name = &e.name;
+            if e.redact {
+                quote::quote! { #name = "<redacted>" }
+            } else {
+                quote::quote! { #name = ?#name }
+            }
+        });
+        let (level_pattern, trace_pattern, source_value) = match variant {
+            ErrorVariant::Named(_) => (
+                quote::quote! { #error_enum_name::#name },
+                quote::quote! { #error_enum_name::#name },
+                None,
+            ),
+            ErrorVariant::Struct(_) => (
+                quote::quote! { #error_enum_name::#name { .. } },
+                quote::quote! { #error_enum_name::#name { #(#field_patterns),* } },
+                None,
+            ),
+            ErrorVariant::SourceStruct(_) => (
+                quote::quote! { #error_enum_name::#name { .. } },
+                quote::quote! { #error_enum_name::#name { source, #(#field_patterns),* } },
+                Some(quote::quote! { source = %source, }),
+            ),
+            ErrorVariant::SourceTuple(_) => (
+                quote::quote! { #error_enum_name::#name(_) },
+                quote::quote! { #error_enum_name::#name(source) },
+                Some(quote::quote! { source = %source, }),
+            ),
+        };
+        level_branches.append_all(quote::quote! {
+            #(#cfg_attributes)*
+            #level_pattern => #level,
+        });
+        trace_branches.append_all(quote::quote! {
+            #(#cfg_attributes)*
+            #trace_pattern => #error_set_path::tracing::event!(
+                #level,
+                code = #code,
+                #source_value
+                #(#field_values,)*
+                "{}",
+                self
+            ),
+        });
+    }
+    let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
+    let generic_names = error_enum.generics.iter().map(|e| &e.ident);
+    token_stream.append_all(quote::quote! {
+        impl #impl_generics #error_enum_name #ty_generics {
+            /// The `tracing` level of the variant, from `#[level(..)]`, or `ERROR` if it has none.
+            pub fn level(&self) -> #error_set_path::tracing::Level {
+                match self {
+                    #level_branches
                 }
-            });
-            if let Some(inner_known_wrapper_type) = inner_source_type_if_concrete_box {
-                match inner_known_wrapper_type {
-                    KnownWrapperTypes::Box(type_path) => {
-                        token_stream.append_all(quote::quote! {
-                            #(#cfg_attributes)*
-                            impl #impl_generics From<#type_path> for #error_enum_name #ty_generics {
-                                fn from(error: #type_path) -> Self {
-                                    #error_enum_name::#variant_name(Box::new(error))
-                                }
-                            }
-                        });
-                    }
-                };
             }
-        } else if is_source_only_struct_type(error_variant) {
-            let (impl_generics, ty_generics) = generic_tokens(&error_enum.generics);
-            let variant_name = &error_variant.name();
-            let cfg_attributes = &error_variant.cfg_attributes();
-            token_stream.append_all(quote::quote! {
-                #(#cfg_attributes)*
-                impl #impl_generics From<#source_type> for #error_enum_name #ty_generics {
-                    fn from(error: #source_type) -> Self {
-                        #error_enum_name::#variant_name { source: error }
-                    }
+        }
+
+        impl #impl_generics #error_enum_name #ty_generics
+        where
+            Self: core::fmt::Display,
+            #(#generic_names: core::fmt::Debug,)*
+        {
+            /// Emits a `tracing` event at [`Self::level`] with the variant's code and fields, and the error's
+            /// display message.
+            pub fn trace(&self) {
+                match self {
+                    #trace_branches
                 }
-            });
-            if let Some(inner_known_wrapper_type) = inner_source_type_if_concrete_box {
-                match inner_known_wrapper_type {
-                    KnownWrapperTypes::Box(type_path) => {
-                        token_stream.append_all(quote::quote! {
-                            #(#cfg_attributes)*
-                            impl #impl_generics From<#type_path> for #error_enum_name #ty_generics {
-                                fn from(error: #type_path) -> Self {
-                                    #error_enum_name::#variant_name { source: Box::new(error) }
-                                }
-                            }
-                        });
-                    }
-                };
             }
         }
-    }
+    });
 }
+
 //************************************************************************//
 
 fn name_to_name(
@@ -718,322 +1303,6 @@ fn source_struct_to_source_struct(
     }
 }
 
-pub(crate) trait Common {
-    fn attributes(&self) -> &Vec<Attribute>;
-    fn cfg_attributes(&self) -> &Vec<Attribute>;
-    fn display(&self) -> Option<&DisplayAttribute>;
-    fn name(&self) -> &Ident;
-    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>>;
-    fn source_type(&self) -> Option<&syn::TypePath>;
-}
-
-#[derive(Clone)]
-pub(crate) enum ErrorVariant {
-    /// e.g. `ErrorVariantNamed,`
-    Named(Named),
-    /// e.g. `ErrorVariantNamed {...}`
-    Struct(Struct),
-    /// e.g. `ErrorVariantNamed(std::io::Error) {...}`
-    SourceStruct(SourceStruct),
-    /// e.g. `ErrorVariantNamed(std::io::Error)`
-    SourceTuple(SourceTuple),
-}
-
-impl Common for ErrorVariant {
-    fn attributes(&self) -> &Vec<Attribute> {
-        match self {
-            ErrorVariant::Named(e) => e.attributes(),
-            ErrorVariant::Struct(e) => e.attributes(),
-            ErrorVariant::SourceStruct(e) => e.attributes(),
-            ErrorVariant::SourceTuple(e) => e.attributes(),
-        }
-    }
-    fn cfg_attributes(&self) -> &Vec<Attribute> {
-        match self {
-            ErrorVariant::Named(e) => e.cfg_attributes(),
-            ErrorVariant::Struct(e) => e.cfg_attributes(),
-            ErrorVariant::SourceStruct(e) => e.cfg_attributes(),
-            ErrorVariant::SourceTuple(e) => e.cfg_attributes(),
-        }
-    }
-    fn display(&self) -> Option<&DisplayAttribute> {
-        match self {
-            ErrorVariant::Named(e) => e.display(),
-            ErrorVariant::Struct(e) => e.display(),
-            ErrorVariant::SourceStruct(e) => e.display(),
-            ErrorVariant::SourceTuple(e) => e.display(),
-        }
-    }
-    fn name(&self) -> &Ident {
-        match self {
-            ErrorVariant::Named(e) => e.name(),
-            ErrorVariant::Struct(e) => e.name(),
-            ErrorVariant::SourceStruct(e) => e.name(),
-            ErrorVariant::SourceTuple(e) => e.name(),
-        }
-    }
-    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
-        match self {
-            ErrorVariant::Named(e) => e.fields(),
-            ErrorVariant::Struct(e) => e.fields(),
-            ErrorVariant::SourceStruct(e) => e.fields(),
-            ErrorVariant::SourceTuple(e) => e.fields(),
-        }
-    }
-    fn source_type(&self) -> Option<&syn::TypePath> {
-        match self {
-            ErrorVariant::Named(e) => e.source_type(),
-            ErrorVariant::Struct(e) => e.source_type(),
-            ErrorVariant::SourceStruct(e) => e.source_type(),
-            ErrorVariant::SourceTuple(e) => e.source_type(),
-        }
-    }
-}
-
-#[derive(Clone)]
-pub(crate) struct Named {
-    pub(crate) attributes: Vec<Attribute>,
-    pub(crate) cfg_attributes: Vec<Attribute>,
-    pub(crate) display: Option<DisplayAttribute>,
-    pub(crate) name: Ident,
-}
-
-impl Common for Named {
-    fn attributes(&self) -> &Vec<Attribute> {
-        &self.attributes
-    }
-    fn cfg_attributes(&self) -> &Vec<Attribute> {
-        &self.cfg_attributes
-    }
-    fn display(&self) -> Option<&DisplayAttribute> {
-        self.display.as_ref()
-    }
-    fn name(&self) -> &Ident {
-        &self.name
-    }
-    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
-        None
-    }
-    fn source_type(&self) -> Option<&syn::TypePath> {
-        None
-    }
-}
-
-#[derive(Clone)]
-pub(crate) struct Struct {
-    pub(crate) attributes: Vec<Attribute>,
-    pub(crate) cfg_attributes: Vec<Attribute>,
-    pub(crate) display: Option<DisplayAttribute>,
-    pub(crate) name: Ident,
-    // Dev Note: This field will never be empty. Otherwise it should just be a [Named]
-    pub(crate) fields: Vec<AstInlineErrorVariantField>,
-}
-
-impl Common for Struct {
-    fn attributes(&self) -> &Vec<Attribute> {
-        &self.attributes
-    }
-    fn cfg_attributes(&self) -> &Vec<Attribute> {
-        &self.cfg_attributes
-    }
-    fn display(&self) -> Option<&DisplayAttribute> {
-        self.display.as_ref()
-    }
-    fn name(&self) -> &Ident {
-        &self.name
-    }
-    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
-        Some(&self.fields)
-    }
-    fn source_type(&self) -> Option<&syn::TypePath> {
-        None
-    }
-}
-
-#[derive(Clone)]
-pub(crate) struct SourceStruct {
-    pub(crate) attributes: Vec<Attribute>,
-    pub(crate) cfg_attributes: Vec<Attribute>,
-    pub(crate) display: Option<DisplayAttribute>,
-    pub(crate) name: Ident,
-    pub(crate) source_type: syn::TypePath,
-    // Dev Note: This field can be empty
-    pub(crate) fields: Vec<AstInlineErrorVariantField>,
-}
-
-impl Common for SourceStruct {
-    fn attributes(&self) -> &Vec<Attribute> {
-        &self.attributes
-    }
-    fn cfg_attributes(&self) -> &Vec<Attribute> {
-        &self.cfg_attributes
-    }
-    fn display(&self) -> Option<&DisplayAttribute> {
-        self.display.as_ref()
-    }
-    fn name(&self) -> &Ident {
-        &self.name
-    }
-    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
-        Some(&self.fields)
-    }
-    fn source_type(&self) -> Option<&syn::TypePath> {
-        Some(&self.source_type)
-    }
-}
-
-#[derive(Clone)]
-pub(crate) struct SourceTuple {
-    pub(crate) attributes: Vec<Attribute>,
-    pub(crate) cfg_attributes: Vec<Attribute>,
-    pub(crate) display: Option<DisplayAttribute>,
-    pub(crate) name: Ident,
-    pub(crate) source_type: syn::TypePath,
-}
-
-impl Common for SourceTuple {
-    fn attributes(&self) -> &Vec<Attribute> {
-        &self.attributes
-    }
-    fn cfg_attributes(&self) -> &Vec<Attribute> {
-        &self.cfg_attributes
-    }
-    fn display(&self) -> Option<&DisplayAttribute> {
-        self.display.as_ref()
-    }
-    fn name(&self) -> &Ident {
-        &self.name
-    }
-    fn fields(&self) -> Option<&Vec<AstInlineErrorVariantField>> {
-        None
-    }
-    fn source_type(&self) -> Option<&syn::TypePath> {
-        Some(&self.source_type)
-    }
-}
-
-//************************************************************************//
-#[derive(Clone)]
-struct ErrorEnumGraphNode {
-    pub(crate) error_enum: ErrorEnum,
-    /// nodes where this error enum can be converted to the other error enum
-    /// 0: index of target enum in graph
-    /// 1: variant mapping
-    ///   0: the from's error_variants's index
-    ///   1: this's error_variants's index
-    pub(crate) froms: Vec<(usize, Vec<(usize, usize)>)>,
-}
-
-impl PartialEq for ErrorEnumGraphNode {
-    fn eq(&self, other: &Self) -> bool {
-        self.error_enum == other.error_enum
-    }
-}
-
-impl ErrorEnumGraphNode {
-    pub(crate) fn new(node: ErrorEnum) -> ErrorEnumGraphNode {
-        ErrorEnumGraphNode {
-            error_enum: node,
-            froms: Vec::new(),
-        }
-    }
-
-    /// Returns an iterator of all the froms of this error enum. And the variant mappings from this to that.
-    pub(crate) fn resolved_froms<'a>(
-        &'a self,
-        graph: &'a [ErrorEnumGraphNode],
-    ) -> impl Iterator<Item = (&'a ErrorEnum, Vec<(&'a ErrorVariant, &'a ErrorVariant)>)> {
-        self.froms.iter().map(|e| {
-            let from = &graph[e.0];
-            let variant_mappings =
-                e.1.iter()
-                    .map(|(from_index, this_index)| {
-                        (
-                            &from.error_enum.error_variants[*from_index],
-                            &self.error_enum.error_variants[*this_index],
-                        )
-                    })
-                    .collect::<Vec<_>>();
-            (&from.error_enum, variant_mappings)
-        })
-    }
-}
-
-#[derive(Clone)]
-pub(crate) struct ErrorEnum {
-    pub(crate) attributes: Vec<Attribute>,
-    pub(crate) vis: Visibility,
-    pub(crate) error_name: Ident,
-    pub(crate) generics: Vec<TypeParam>,
-    pub(crate) disabled: Disabled,
-    pub(crate) error_variants: Vec<ErrorVariant>,
-}
-
-impl core::hash::Hash for ErrorEnum {
-    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-        self.error_name.hash(state);
-    }
-}
-
-impl Eq for ErrorEnum {}
-
-impl PartialEq for ErrorEnum {
-    fn eq(&self, other: &Self) -> bool {
-        self.error_name == other.error_name
-    }
-}
-
-//************************************************************************//
-
-fn extract_string_if_str_literal(input: TokenStream) -> Option<String> {
-    if let Ok(expr) = syn::parse2::<Lit>(input) {
-        if let Lit::Str(lit) = expr {
-            return Some(lit.value());
-        }
-    }
-    None
-}
-
-// Dev Note: naive implementation.
-fn is_format_str(input: &str) -> bool {
-    let mut interpolation_candidate_found = false;
-    let mut last_char = 'a';
-
-    let mut start_count = 0;
-    let mut end_count = 0;
-
-    for c in input.chars() {
-        if c == '{' {
-            if last_char == '{' {
-                last_char = 'a';
-                start_count -= 1;
-                continue;
-            }
-            start_count += 1;
-        } else if c == '}' {
-            if last_char == '}' {
-                last_char = 'a';
-                end_count -= 1;
-                continue;
-            }
-            end_count += 1;
-            if start_count == end_count {
-                interpolation_candidate_found = true;
-            }
-        }
-        last_char = c;
-    }
-    return interpolation_candidate_found && start_count == end_count;
-}
-
-fn is_opaque(input: TokenStream) -> bool {
-    if let Ok(ident) = syn::parse2::<Ident>(input) {
-        ident == "opaque"
-    } else {
-        false
-    }
-}
-
 //************************************************************************//
 
 fn generic_tokens(generics: &Vec<TypeParam>) -> (Option<TokenStream>, Option<TokenStream>) {
@@ -1047,115 +1316,3 @@ fn generic_tokens(generics: &Vec<TypeParam>) -> (Option<TokenStream>, Option<Tok
 
     (Some(impl_clause), Some(ty_clause))
 }
-
-//************************************************************************//
-
-pub(crate) fn is_source_tuple_type(error_variant: &ErrorVariant) -> bool {
-    return error_variant.source_type().is_some() && error_variant.fields().is_none();
-}
-
-pub(crate) fn is_source_only_struct_type(error_variant: &ErrorVariant) -> bool {
-    return error_variant.source_type().is_some()
-        && error_variant
-            .fields()
-            .as_ref()
-            .is_some_and(|e| e.is_empty());
-}
-
-pub(crate) fn is_source_struct_type(error_variant: &ErrorVariant) -> bool {
-    return error_variant.source_type().is_some() && error_variant.fields().as_ref().is_some();
-}
-
-/// To determine if [this] can be converted into [that] without dropping values.
-/// Ignoring backtrace (since this is generated in the `From` impl if missing) and display.
-/// This does not mean [this] is a subset of [that].
-/// Why do they need to be exact?
-/// e.g.
-/// ```ignore
-/// X {
-///   a: String,
-///   b: u32,
-/// }
-/// ```
-/// The above can be converted to the below, by droping the `b`. Even though the below could be considered a "subset".
-/// ```ignore
-/// Y {
-///   a: String
-/// }
-/// ```ignore
-/// If the below was also in the target enum, it would also be valid conversion target
-/// ```ignore
-/// Z {
-///  b: u32
-/// }
-/// ```
-/// Thus, the names and shapes must be exactly the same to avoid this.
-/// Note, there can multiple source tuples or sources only structs with the same wrapped error types (different names).
-/// The first that is encountered becomes the `From` impl of that source error type.
-/// To ensure the correct one is selected, pay attention to `X = A || B` ordering
-/// or define your own `X = { IoError(std::io::Error) } || A || B`
-///
-/// Another example:
-/// ```ignore
-///  N1 {
-///     field: i32
-///  }
-/// ```
-/// ==
-/// ```ignore
-/// N1 {
-///     field: i32
-///  }
-/// ```
-/// !=
-/// ```ignore
-/// N2 {
-///     field: i32
-///  }
-/// ```
-pub(crate) fn is_conversion_target(this: &ErrorVariant, that: &ErrorVariant) -> bool {
-    return match (&this.source_type(), &that.source_type()) {
-        (Some(this_source_type), Some(other_source_type)) => {
-            this_source_type.path == other_source_type.path
-                && this.name() == that.name()
-                && this.fields() == that.fields()
-        }
-        (None, None) => this.name() == that.name() && this.fields() == that.fields(),
-        _ => false,
-    };
-}
-
-//************************************************************************//
-
-fn maybe_extract_known_wrapper_types(ty: &TypePath) -> Option<KnownWrapperTypes<'_>> {
-    let last_part = ty
-        .path
-        .segments
-        .last()
-        .expect("If segments exist there should be more than one.");
-    let wrapper = match &*last_part.ident.to_string() {
-        "Box" => KnownWrapperTypes::Box,
-        _ => return None,
-    };
-    match &last_part.arguments {
-        PathArguments::AngleBracketed(box_args) => {
-            if box_args.args.len() != 1 {
-                return None;
-            }
-            let box_arg = box_args.args.first().unwrap();
-            match box_arg {
-                syn::GenericArgument::Type(box_type) => match box_type {
-                    syn::Type::Path(valid_box_type) => Some(wrapper(valid_box_type)),
-                    _ => None,
-                },
-                _ => None,
-            }
-        }
-        _ => None,
-    }
-}
-
-enum KnownWrapperTypes<'a> {
-    Box(&'a TypePath),
-    // TracedError(&'a TypePath),
-}
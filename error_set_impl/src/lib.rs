@@ -1,58 +1,73 @@
-mod ast;
 mod expand;
-mod resolve;
-mod validate;
 
-use ast::AstErrorSet;
+use error_set_core::ast::{AstErrorKind, AstErrorSet, AstErrorSetPart, AstInlineOrRefError};
+use error_set_core::resolve_error_set;
+use error_set_core::validate::validate_part;
 use expand::expand;
 use quote::TokenStreamExt;
-use resolve::resolve;
-use validate::validate;
-
-use crate::ast::AstErrorKind;
 
 #[proc_macro]
 pub fn error_set(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let error_set = syn::parse_macro_input!(tokens as AstErrorSet);
-    let mut error_enum_decls = Vec::new();
-    let mut error_struct_decls = Vec::new();
-    for item in error_set.set_items.into_iter() {
-        match item {
-            AstErrorKind::Enum(error_enum_decl) => {
-                error_enum_decls.push(error_enum_decl);
-            }
-            AstErrorKind::Struct(struct_decl) => {
-                error_struct_decls.push(struct_decl);
-            }
-        }
-    }
-    let error_enums = match resolve(error_enum_decls) {
+    let resolved_error_set = match resolve_error_set(error_set) {
         Ok(ok) => ok,
         Err(err) => {
             return err.into_compile_error().into();
         }
     };
-    if let Err(err) = validate(&error_enums) {
-        return err.into_compile_error().into();
-    }
-    expand(error_enums, error_struct_decls).into()
+    expand(resolved_error_set).into()
 }
 
 #[proc_macro]
 pub fn error_set_part(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let error_set = syn::parse_macro_input!(tokens as AstErrorSet);
-    let mut token_stream = proc_macro2::TokenStream::new();
-    for item in error_set.set_items.into_iter() {
-        let name = match item {
+    let error_set_part = syn::parse_macro_input!(tokens as AstErrorSetPart);
+    let module = match error_set_part.module {
+        Some(module) => quote::quote! { #module },
+        None => quote::quote! { crate::error_set },
+    };
+    let mut error_enum_decls = Vec::new();
+    let mut error_struct_decls = Vec::new();
+    for item in error_set_part.error_set.set_items.into_iter() {
+        match item {
             AstErrorKind::Enum(error_enum_decl) => {
-                error_enum_decl.error_name
+                error_enum_decls.push(error_enum_decl);
             }
             AstErrorKind::Struct(struct_decl) => {
-                struct_decl.r#struct.ident
+                error_struct_decls.push(struct_decl);
             }
+        }
+    }
+    if let Err(err) = validate_part(&error_enum_decls, &error_struct_decls) {
+        return err.into_compile_error().into();
+    }
+    let mut ref_names: Vec<syn::Ident> = Vec::new();
+    for part in error_enum_decls.iter().flat_map(|e| &e.parts) {
+        let AstInlineOrRefError::Ref(ref_part) = part else {
+            continue;
         };
+        if !ref_names.contains(&ref_part.name) {
+            ref_names.push(ref_part.name.clone());
+        }
+    }
+    let names = error_enum_decls
+        .into_iter()
+        .map(|e| e.error_name)
+        .chain(error_struct_decls.into_iter().map(|e| e.r#struct.ident));
+    let mut token_stream = proc_macro2::TokenStream::new();
+    for name in names {
+        // Dev Note: `#module` has the user's spans, so unused imports would otherwise be reported
+        token_stream.append_all(quote::quote! {
+            #[allow(unused_imports)]
+            use #module::#name;
+        });
+    }
+    // Referenced sets may be declared in other parts, so rustc checks they exist. Dev Note: Nested, so a missing set
+    // is reported at the reference.
+    for ref_name in ref_names {
+        let ref_import = quote::quote_spanned! {ref_name.span()=> #ref_name as _ };
         token_stream.append_all(quote::quote! {
-            use crate::error_set::#name;
+            #[allow(unused_imports)]
+            use #module::{#ref_import};
         });
     }
     token_stream.into()
@@ -1,14 +1,18 @@
 // This file is auto-generated
 
 error_set::error_set! {
-	// From `src/nested/mod.rs:3`
-C := {Field1,}
+    // From `src/nested/mod.rs:3`
+    C := {
+        Field1,
+    }
 
-	// From `src/top_level.rs:3`
-    B := {Field2,} || A
+    // From `src/top_level.rs:3`
+    B := {
+        Field2,
+    } || A
 
-	// From `src/top_level.rs:9`
-    A := {Field,}
-
-
-}
\ No newline at end of file
+    // From `src/top_level.rs:9`
+    A := {
+        Field,
+    }
+}
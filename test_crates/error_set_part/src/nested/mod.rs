@@ -1,5 +1,6 @@
 use error_set::error_set_part;
 
 error_set_part! {
+#![module(crate::error_set)]
 C := {Field1,}
 }
\ No newline at end of file
@@ -0,0 +1,37 @@
+//! `error_set` depended on under another name, so generated code must not refer to `::error_set`.
+
+use errs::error_set;
+
+error_set! {
+    PlainError := {
+        A,
+    }
+    FetchError := {
+        #[retryable]
+        Timeout,
+        NotFound,
+    }
+    JobError := {
+        #[retryable(source)]
+        Fetch(FetchError),
+        #[retryable(source)]
+        Plain(PlainError),
+    }
+    #[exit_code(2)]
+    CliError := {
+        Usage,
+        #[exit_code(64)]
+        MissingArgument,
+    }
+}
+
+fn main() {
+    assert!(matches!(PlainError::A, PlainError::A));
+    assert!(FetchError::Timeout.is_retryable());
+    assert!(!FetchError::NotFound.is_retryable());
+    assert!(errs::Retryable::is_retryable(&JobError::Fetch(FetchError::Timeout)));
+    assert!(!JobError::Plain(PlainError::A).is_retryable());
+    assert_eq!(CliError::Usage.exit_code(), 2);
+    assert_eq!(errs::ToExitCode::exit_code(&CliError::MissingArgument), 64);
+    println!("Success");
+}